@@ -0,0 +1,69 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Opt-in accessibility policies for native output; unset values inherit from the parent theme.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccessibilityPolicy {
+    /// Minimum size in dp of tappable elements (Material recommends 48)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_touch_target: Option<f32>,
+    /// Bounds for the user's font scale (`scaledDensity / density`), e.g. 0.85 and 1.3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_font_scale: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_font_scale: Option<f32>,
+}
+
+impl AccessibilityPolicy {
+    /// Fill unset values from `parent`
+    pub fn inherit(&mut self, parent: &AccessibilityPolicy) {
+        self.min_touch_target = self.min_touch_target.or(parent.min_touch_target);
+        self.min_font_scale = self.min_font_scale.or(parent.min_font_scale);
+        self.max_font_scale = self.max_font_scale.or(parent.max_font_scale);
+    }
+
+    /// Scaled density with the font scale clamped to the policy's bounds
+    pub fn clamp_scaled_density(&self, density: f32, scaled_density: f32) -> f32 {
+        if density <= 0.0 {
+            return scaled_density;
+        }
+        let scale = scaled_density / density;
+        let clamped = scale.max(self.min_font_scale.unwrap_or(scale)).min(self.max_font_scale.unwrap_or(scale));
+        density * clamped
+    }
+}
+
+const INTERACTIVE_TAGS: [&str; 6] = ["button", "input", "select", "textarea", "a", "summary"];
+
+/// Whether an element is tappable: an interactive tag or `cursor: pointer`, unless
+/// pointer events are disabled.
+pub fn is_tappable(tag: &str, styles: &IndexMap<String, serde_json::Value>) -> bool {
+    if styles.get("pointerEvents").and_then(|v| v.as_str()) == Some("none") {
+        return false;
+    }
+    INTERACTIVE_TAGS.contains(&tag.to_lowercase().as_str())
+        || styles.get("cursor").and_then(|v| v.as_str()) == Some("pointer")
+        || styles.get("androidClickable").and_then(|v| v.as_bool()) == Some(true)
+}
+
+/// Raise `minWidth`/`minHeight` (already in px) to the touch target. A fixed `width`/`height`
+/// smaller than the target is left alone and the difference becomes `androidHitSlop` instead.
+pub fn apply_touch_target(styles: &mut IndexMap<String, serde_json::Value>, target_px: i64) {
+    let mut slop = IndexMap::new();
+    for (size, min, axis) in [("width", "minWidth", "horizontal"), ("height", "minHeight", "vertical")] {
+        match styles.get(size).and_then(|v| v.as_i64()) {
+            Some(fixed) if fixed < target_px => {
+                slop.insert(axis, (target_px - fixed + 1) / 2);
+            }
+            Some(_) => {}
+            None => {
+                let current = styles.get(min).and_then(|v| v.as_i64()).unwrap_or(0);
+                styles.insert(min.to_string(), json!(current.max(target_px)));
+            }
+        }
+    }
+    if !slop.is_empty() {
+        styles.insert("androidHitSlop".to_string(), json!(slop));
+    }
+}
@@ -0,0 +1,402 @@
+use indexmap::IndexMap;
+use serde_json::json;
+use crate::units::UnitContext;
+use crate::color::evaluate_color;
+use crate::utils::{camel_case, resolve_vars, split_top_level};
+use crate::CssProps;
+
+/// Keyframe selector (`from`, `50%`, `0%, 100%`) -> properties at that point
+pub type Keyframes = IndexMap<String, CssProps>;
+
+/// Tailwind's built-in keyframes, used unless a theme defines the same name
+pub fn builtin_keyframes() -> IndexMap<String, Keyframes> {
+    let frames = |list: &[(&str, &[(&str, &str)])]| -> Keyframes {
+        list.iter()
+            .map(|(sel, props)| (sel.to_string(), props.iter().map(|(k, v)| (k.to_string(), json!(v).into())).collect()))
+            .collect()
+    };
+    let mut out = IndexMap::new();
+    out.insert("spin".to_string(), frames(&[("from", &[("transform", "rotate(0deg)")]), ("to", &[("transform", "rotate(360deg)")])]));
+    out.insert("ping".to_string(), frames(&[("75%, 100%", &[("transform", "scale(2)"), ("opacity", "0")])]));
+    out.insert("pulse".to_string(), frames(&[("0%, 100%", &[("opacity", "1")]), ("50%", &[("opacity", ".5")])]));
+    out.insert(
+        "bounce".to_string(),
+        frames(&[
+            ("0%, 100%", &[("transform", "translateY(-25%)"), ("animation-timing-function", "cubic-bezier(0.8, 0, 1, 1)")]),
+            ("50%", &[("transform", "none"), ("animation-timing-function", "cubic-bezier(0, 0, 0.2, 1)")]),
+        ]),
+    );
+    out
+}
+
+/// `animate-*` utility value. `animation.<name>` variables override the built-ins; any other
+/// name with keyframes runs once for one second.
+pub fn animate_utility(name: &str, vars: &IndexMap<String, String>, keyframes: &IndexMap<String, Keyframes>) -> Option<String> {
+    if let Some(value) = vars.get(&format!("animation.{}", name)) {
+        return Some(value.clone());
+    }
+    Some(match name {
+        "none" => "none".to_string(),
+        "spin" => "spin 1s linear infinite".to_string(),
+        "ping" => "ping 1s cubic-bezier(0, 0, 0.2, 1) infinite".to_string(),
+        "pulse" => "pulse 2s cubic-bezier(0.4, 0, 0.6, 1) infinite".to_string(),
+        "bounce" => "bounce 1s infinite".to_string(),
+        other if keyframes.contains_key(other) => format!("{} 1s ease", other),
+        _ => return None,
+    })
+}
+
+/// Media condition for a `motion-reduce:` / `motion-safe:` prefixed class
+pub fn motion_variant(class: &str) -> Option<&'static str> {
+    let prefixes = class.rsplit_once(':')?.0;
+    prefixes.split(':').find_map(|p| match p {
+        "motion-reduce" => Some("(prefers-reduced-motion: reduce)"),
+        "motion-safe" => Some("(prefers-reduced-motion: no-preference)"),
+        _ => None,
+    })
+}
+
+/// Whether a class applies natively given the host's reduced-motion setting
+pub fn motion_variant_applies(class: &str, reduced_motion: bool) -> bool {
+    match motion_variant(class) {
+        Some(cond) if cond.contains("reduce)") => reduced_motion,
+        Some(_) => !reduced_motion,
+        None => true,
+    }
+}
+
+/// One animation from an `animation` shorthand
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationSpec {
+    pub name: String,
+    pub duration_ms: f32,
+    pub delay_ms: f32,
+    pub easing: String,
+    /// -1 for `infinite`
+    pub iterations: f32,
+    pub direction: String,
+    pub fill_mode: String,
+}
+
+/// Parse a single-animation `animation` shorthand. Returns `None` for `none` or without a name.
+pub fn parse_animation(value: &str) -> Option<AnimationSpec> {
+    let mut spec = AnimationSpec {
+        name: String::new(),
+        duration_ms: 0.0,
+        delay_ms: 0.0,
+        easing: "ease".to_string(),
+        iterations: 1.0,
+        direction: "normal".to_string(),
+        fill_mode: "none".to_string(),
+    };
+    let mut times = 0;
+    let first = split_top_level(value, ',').into_iter().next()?;
+    for token in split_top_level(&first, ' ') {
+        if let Some(ms) = parse_time_ms(&token) {
+            if times == 0 { spec.duration_ms = ms } else { spec.delay_ms = ms }
+            times += 1;
+        } else if is_easing(&token) {
+            spec.easing = token;
+        } else if token == "infinite" {
+            spec.iterations = -1.0;
+        } else if let Ok(n) = token.parse::<f32>() {
+            spec.iterations = n;
+        } else if matches!(token.as_str(), "normal" | "reverse" | "alternate" | "alternate-reverse") {
+            spec.direction = token;
+        } else if matches!(token.as_str(), "forwards" | "backwards" | "both") {
+            spec.fill_mode = token;
+        } else if !matches!(token.as_str(), "running" | "paused" | "none") {
+            spec.name = token;
+        }
+    }
+    (!spec.name.is_empty()).then_some(spec)
+}
+
+/// `150ms` / `0.3s` in milliseconds
+pub fn parse_time_ms(token: &str) -> Option<f32> {
+    if let Some(ms) = token.strip_suffix("ms") {
+        return ms.parse().ok();
+    }
+    token.strip_suffix('s')?.parse::<f32>().ok().map(|s| s * 1000.0)
+}
+
+pub fn is_easing(token: &str) -> bool {
+    matches!(token, "linear" | "ease" | "ease-in" | "ease-out" | "ease-in-out" | "step-start" | "step-end")
+        || token.starts_with("cubic-bezier(")
+        || token.starts_with("steps(")
+        || token.starts_with("linear(")
+}
+
+/// Keyframe offsets in 0..=1 for a selector such as `from`, `50%` or `0%, 100%`
+fn keyframe_offsets(selector: &str) -> Vec<f32> {
+    selector
+        .split(',')
+        .filter_map(|s| match s.trim() {
+            "from" => Some(0.0),
+            "to" => Some(1.0),
+            s => s.strip_suffix('%')?.trim().parse::<f32>().ok().map(|p| p / 100.0),
+        })
+        .collect()
+}
+
+const TRANSFORM_TRACKS: [(&str, f32); 5] =
+    [("rotation", 0.0), ("scaleX", 1.0), ("scaleY", 1.0), ("translationX", 0.0), ("translationY", 0.0)];
+
+/// Split a CSS `transform` into Android view properties. Lengths become px, percentages
+/// (relative to the view's own size) stay strings for the host.
+fn transform_tracks(value: &str, units: &UnitContext) -> Vec<(&'static str, serde_json::Value)> {
+    let mut out = Vec::new();
+    let length = |v: &str| if v.ends_with('%') { json!(v) } else { units.to_px(&json!(v)).unwrap_or(json!(0)) };
+    for func in split_top_level(value, ' ') {
+        let Some((name, args)) = func.strip_suffix(')').and_then(|f| f.split_once('(')) else { continue };
+        let args: Vec<String> = split_top_level(args, ',');
+        let arg = |i: usize| args.get(i).map(|s| s.trim().to_string());
+        match name {
+            "rotate" | "rotateZ" => {
+                if let Some(deg) = arg(0).and_then(|a| parse_degrees(&a)) {
+                    out.push(("rotation", json!(deg)));
+                }
+            }
+            "scale" => {
+                let x = arg(0).and_then(|a| a.parse::<f32>().ok()).unwrap_or(1.0);
+                let y = arg(1).and_then(|a| a.parse::<f32>().ok()).unwrap_or(x);
+                out.push(("scaleX", json!(x)));
+                out.push(("scaleY", json!(y)));
+            }
+            "scaleX" | "scaleY" => {
+                if let Some(s) = arg(0).and_then(|a| a.parse::<f32>().ok()) {
+                    out.push((if name == "scaleX" { "scaleX" } else { "scaleY" }, json!(s)));
+                }
+            }
+            "translate" => {
+                if let Some(x) = arg(0) {
+                    out.push(("translationX", length(&x)));
+                }
+                out.push(("translationY", arg(1).map(|y| length(&y)).unwrap_or(json!(0))));
+            }
+            "translateX" | "translateY" => {
+                if let Some(v) = arg(0) {
+                    out.push((if name == "translateX" { "translationX" } else { "translationY" }, length(&v)));
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+fn parse_degrees(s: &str) -> Option<f32> {
+    if let Some(d) = s.strip_suffix("deg") {
+        return d.parse().ok();
+    }
+    if let Some(t) = s.strip_suffix("turn") {
+        return t.parse::<f32>().ok().map(|t| t * 360.0);
+    }
+    if let Some(r) = s.strip_suffix("rad") {
+        return r.parse::<f32>().ok().map(|r| r.to_degrees());
+    }
+    (s == "0").then_some(0.0)
+}
+
+/// Structured Android animation: timing from the shorthand plus one track per animated
+/// property (`alpha`, `rotation`, `scaleX`, `translationY`, ...). Each track lists
+/// `{offset, value, easing?}` keyframes; missing start/end frames of transform and alpha
+/// tracks use the property's resting value. Values are resolved against the theme variables,
+/// and color values are emitted as Android `#AARRGGBB`.
+pub fn android_animation(
+    spec: &AnimationSpec,
+    keyframes: &Keyframes,
+    units: &UnitContext,
+    vars: &IndexMap<String, String>,
+    current_color: Option<&str>,
+) -> serde_json::Value {
+    let mut tracks: IndexMap<String, Vec<(f32, serde_json::Value, Option<String>)>> = IndexMap::new();
+    let mut resets = Vec::new();
+    for (selector, props) in keyframes {
+        let easing = props
+            .iter()
+            .find(|(k, _)| camel_case(k) == "animationTimingFunction")
+            .and_then(|(_, v)| v.as_str())
+            .map(|s| s.to_string());
+        for offset in keyframe_offsets(selector) {
+            for (key, value) in props {
+                let key = camel_case(key);
+                let text = value.as_str().map(|s| s.to_string()).unwrap_or_else(|| value.to_string());
+                let text = resolve_vars(&text, vars);
+                match key.as_str() {
+                    "animationTimingFunction" => {}
+                    "transform" if text.trim() == "none" => resets.push((offset, easing.clone())),
+                    "transform" => {
+                        for (prop, v) in transform_tracks(&text, units) {
+                            tracks.entry(prop.to_string()).or_default().push((offset, v, easing.clone()));
+                        }
+                    }
+                    "opacity" => {
+                        let alpha = text.parse::<f32>().map(|a| json!(a)).unwrap_or(json!(text));
+                        tracks.entry("alpha".to_string()).or_default().push((offset, alpha, easing.clone()));
+                    }
+                    _ => {
+                        let value = match evaluate_color(&text, current_color, vars) {
+                            Some(color) => json!(color.to_android_hex()),
+                            None => json!(text),
+                        };
+                        tracks.entry(key).or_default().push((offset, value, easing.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut out_tracks = Vec::new();
+    for (property, mut frames) in tracks {
+        let rest = TRANSFORM_TRACKS
+            .iter()
+            .chain(&[("alpha", 1.0)])
+            .find(|(name, _)| *name == property)
+            .map(|(_, v)| *v);
+        if TRANSFORM_TRACKS.iter().any(|(name, _)| *name == property) {
+            for (offset, easing) in &resets {
+                frames.push((*offset, json!(rest.unwrap_or(0.0)), easing.clone()));
+            }
+        }
+        frames.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(rest) = rest {
+            if frames.first().is_some_and(|f| f.0 > 0.0) {
+                frames.insert(0, (0.0, json!(rest), None));
+            }
+            if frames.last().is_some_and(|f| f.0 < 1.0) {
+                frames.push((1.0, json!(rest), None));
+            }
+        }
+        let frames: Vec<serde_json::Value> = frames
+            .into_iter()
+            .map(|(offset, value, easing)| {
+                let mut frame = json!({ "offset": offset, "value": value });
+                if let Some(easing) = easing {
+                    frame["easing"] = json!(easing);
+                }
+                frame
+            })
+            .collect();
+        out_tracks.push(json!({ "property": property, "keyframes": frames }));
+    }
+
+    json!({
+        "name": spec.name,
+        "duration": spec.duration_ms,
+        "delay": spec.delay_ms,
+        "easing": spec.easing,
+        "iterations": spec.iterations,
+        "direction": spec.direction,
+        "fillMode": spec.fill_mode,
+        "tracks": out_tracks,
+    })
+}
+
+/// Android view properties animated when a CSS property transitions
+fn android_transition_properties(css_property: &str) -> &'static [&'static str] {
+    match css_property {
+        "all" => &["all"],
+        "color" => &["textColor"],
+        "background-color" | "background" => &["backgroundColor"],
+        "border-color" => &["strokeColor"],
+        "fill" | "stroke" => &["tint"],
+        "opacity" => &["alpha"],
+        "transform" | "translate" | "rotate" | "scale" => &["rotation", "scaleX", "scaleY", "translationX", "translationY"],
+        "box-shadow" => &["elevation"],
+        "width" => &["width"],
+        "height" => &["height"],
+        _ => &[],
+    }
+}
+
+/// Android interpolator for a CSS timing function. Tailwind's curves are the Material ones.
+pub fn android_interpolator(easing: &str) -> serde_json::Value {
+    let compact: String = easing.chars().filter(|c| !c.is_whitespace()).collect();
+    json!(match compact.as_str() {
+        "linear" => "linear",
+        "ease-in" => "accelerate",
+        "ease-out" => "decelerate",
+        "ease" | "ease-in-out" => "accelerate_decelerate",
+        "cubic-bezier(0.4,0,1,1)" => "fast_out_linear_in",
+        "cubic-bezier(0,0,0.2,1)" => "linear_out_slow_in",
+        "cubic-bezier(0.4,0,0.2,1)" => "fast_out_slow_in",
+        other => {
+            let points: Option<Vec<f64>> = other
+                .strip_prefix("cubic-bezier(")
+                .and_then(|p| p.strip_suffix(')'))
+                .map(|p| p.split(',').filter_map(|n| n.parse().ok()).collect());
+            return match points {
+                Some(points) if points.len() == 4 => json!({ "type": "path", "controlPoints": points }),
+                _ => json!("accelerate_decelerate"),
+            };
+        }
+    })
+}
+
+/// Resolve a whole-value `var(--name, fallback)` against custom properties set on the same
+/// element (`--tw-duration` from `duration-*`); other values are returned as-is.
+fn element_var(styles: &IndexMap<String, serde_json::Value>, value: &str) -> String {
+    let Some(inner) = value.trim().strip_prefix("var(").and_then(|v| v.strip_suffix(')')) else {
+        return value.to_string();
+    };
+    let (name, fallback) = inner.split_once(',').unwrap_or((inner, ""));
+    match styles.get(name.trim()).and_then(|v| v.as_str()) {
+        Some(set) => set.to_string(),
+        None => fallback.trim().to_string(),
+    }
+}
+
+/// Normalized Android transition from the camelCased `transition*` styles:
+/// `{properties, duration, delay, interpolator}`, or `None` when nothing would animate.
+/// Only the first duration/delay/easing are used; Android animates every listed property alike.
+pub fn android_transition(styles: &IndexMap<String, serde_json::Value>) -> Option<serde_json::Value> {
+    let text = |key: &str| styles.get(key).and_then(|v| v.as_str()).map(|s| element_var(styles, s));
+    let mut css_properties = Vec::new();
+    let (mut duration, mut delay, mut easing) = (None, None, None);
+    if let Some(shorthand) = text("transition") {
+        for item in split_top_level(&shorthand, ',') {
+            let mut times = 0;
+            let mut property = "all".to_string();
+            for token in split_top_level(&item, ' ') {
+                if let Some(ms) = parse_time_ms(&token) {
+                    if times == 0 { duration = duration.or(Some(ms)) } else { delay = delay.or(Some(ms)) }
+                    times += 1;
+                } else if is_easing(&token) {
+                    easing = easing.or(Some(token));
+                } else {
+                    property = token;
+                }
+            }
+            css_properties.push(property);
+        }
+    }
+    if let Some(list) = text("transitionProperty") {
+        css_properties = split_top_level(&list, ',');
+    }
+    duration = text("transitionDuration").and_then(|d| parse_time_ms(split_top_level(&d, ',').first()?)).or(duration);
+    delay = text("transitionDelay").and_then(|d| parse_time_ms(split_top_level(&d, ',').first()?)).or(delay);
+    easing = text("transitionTimingFunction").and_then(|e| split_top_level(&e, ',').into_iter().next()).or(easing);
+
+    let duration = duration.filter(|d| *d > 0.0)?;
+    if css_properties.is_empty() {
+        css_properties.push("all".to_string());
+    }
+    let mut properties: Vec<&str> = Vec::new();
+    for p in &css_properties {
+        for android in android_transition_properties(&crate::utils::kebab_case(p.trim())) {
+            if !properties.contains(android) {
+                properties.push(android);
+            }
+        }
+    }
+    if properties.is_empty() {
+        return None;
+    }
+    Some(json!({
+        "properties": properties,
+        "duration": duration,
+        "delay": delay.unwrap_or(0.0),
+        "interpolator": android_interpolator(easing.as_deref().unwrap_or("ease")),
+    }))
+}
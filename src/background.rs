@@ -0,0 +1,98 @@
+use indexmap::IndexMap;
+use serde_json::json;
+
+const SIDES: [&str; 4] = ["top", "right", "bottom", "left"];
+
+fn px(styles: &IndexMap<String, serde_json::Value>, key: &str) -> Option<f64> {
+    styles.get(key).and_then(|v| v.as_f64())
+}
+
+fn text<'a>(styles: &'a IndexMap<String, serde_json::Value>, key: &str) -> Option<&'a str> {
+    styles.get(key).and_then(|v| v.as_str())
+}
+
+/// Physical corner radii in px, clockwise from top-left. Logical corners resolve against the
+/// layout direction and override the physical ones.
+fn corner_radii(styles: &IndexMap<String, serde_json::Value>, rtl: bool) -> [f64; 4] {
+    let base = px(styles, "borderRadius").unwrap_or(0.0);
+    let mut radii = [
+        px(styles, "borderTopLeftRadius").unwrap_or(base),
+        px(styles, "borderTopRightRadius").unwrap_or(base),
+        px(styles, "borderBottomRightRadius").unwrap_or(base),
+        px(styles, "borderBottomLeftRadius").unwrap_or(base),
+    ];
+    for (radius, key) in radii.iter_mut().zip(crate::direction::logical_corners(rtl)) {
+        if let Some(r) = px(styles, key) {
+            *radius = r;
+        }
+    }
+    radii
+}
+
+/// Stroke for one physical side; `None` when the side draws nothing
+fn side_stroke(styles: &IndexMap<String, serde_json::Value>, side: &str, logical: Option<&str>) -> Option<serde_json::Value> {
+    let cap = |s: &str| s[..1].to_uppercase() + &s[1..];
+    let pick = |suffix: &str| {
+        logical
+            .and_then(|l| styles.get(&format!("border{}{}", l, suffix)))
+            .or_else(|| styles.get(&format!("border{}{}", cap(side), suffix)))
+            .or_else(|| styles.get(&format!("border{}", suffix)))
+    };
+    let style = pick("Style").and_then(|v| v.as_str()).unwrap_or("solid");
+    let width = pick("Width").and_then(|v| v.as_f64()).unwrap_or(0.0);
+    if width <= 0.0 || style == "none" || style == "hidden" {
+        return None;
+    }
+    Some(json!({
+        "width": width as i64,
+        // CSS draws borders in currentColor by default
+        "color": pick("Color").or_else(|| styles.get("color")).cloned().unwrap_or(serde_json::Value::Null),
+        "style": style,
+    }))
+}
+
+/// One drawable description combining the flat background keys: shape, corner radii, fill,
+/// per-side stroke, gradient, ripple and clipping. Expects px lengths and Android hex colors,
+/// so it runs at the end of `android_styles_for`. Returns `None` when nothing is drawn.
+pub fn android_background(styles: &IndexMap<String, serde_json::Value>, rtl: bool) -> Option<serde_json::Value> {
+    let radii: Vec<serde_json::Value> = match styles.get("androidCornerRadii").and_then(|v| v.as_array()) {
+        // Elliptical corners already come as x/y pairs, with logical corners resolved
+        Some(pairs) => pairs.clone(),
+        None => corner_radii(styles, rtl).iter().map(|r| json!(*r as i64)).collect(),
+    };
+    let (start, end) = if rtl { ("right", "left") } else { ("left", "right") };
+    let mut stroke = serde_json::Map::new();
+    for side in SIDES {
+        let logical = if side == start { Some("Start") } else if side == end { Some("End") } else { None };
+        if let Some(s) = side_stroke(styles, side, logical) {
+            stroke.insert(side.to_string(), s);
+        }
+    }
+    // Android hex with a zero alpha (`#00RRGGBB`) fills nothing
+    let fill = styles.get("backgroundColor").filter(|v| !v.as_str().is_some_and(|c| c.len() == 9 && c.starts_with("#00"))).cloned();
+    let gradient = styles.get("androidGradient").cloned();
+    let ripple = styles.get("rippleColor").cloned();
+    let clip = text(styles, "overflow") == Some("hidden");
+    let rounded = radii.iter().any(|r| r.as_f64().is_some_and(|r| r > 0.0));
+    if fill.is_none() && gradient.is_none() && ripple.is_none() && stroke.is_empty() && !rounded {
+        return None;
+    }
+
+    // A square whose corners are at least half its size is drawn as a circle
+    let (width, height) = (px(styles, "width"), px(styles, "height"));
+    let oval = match (width, height) {
+        (Some(w), Some(h)) if w == h && radii.len() == 4 => radii.iter().all(|r| r.as_f64().is_some_and(|r| r >= w / 2.0)),
+        _ => false,
+    };
+    let uniform = stroke.len() == 4 && SIDES.iter().all(|s| stroke.get(*s) == stroke.get("top"));
+    Some(json!({
+        "shape": if oval { "oval" } else { "rectangle" },
+        "radii": radii,
+        "fill": fill,
+        "stroke": stroke,
+        "uniformStroke": uniform,
+        "gradient": gradient,
+        "ripple": ripple,
+        "clipChildren": clip,
+    }))
+}
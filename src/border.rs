@@ -0,0 +1,84 @@
+use crate::utils::split_top_level;
+use crate::value::CssValue;
+
+const BORDER_STYLES: &[&str] = &[
+    "none", "hidden", "dotted", "dashed", "solid", "double", "groove", "ridge", "inset", "outset",
+];
+
+/// The parsed components of a `border`/`outline` shorthand. Missing components fall back to
+/// their CSS initial values (`medium`, `none`, `currentColor`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorderShorthand {
+    pub width: String,
+    pub style: String,
+    pub color: String,
+}
+
+/// Parse `<width> || <style> || <color>` in any order.
+/// Returns `None` if a component repeats or a token can't be classified.
+pub fn parse_border_shorthand(value: &str) -> Option<BorderShorthand> {
+    parse_shorthand(value, false)
+}
+
+/// Like `parse_border_shorthand`, but also accepts the outline-only `auto` style.
+pub fn parse_outline_shorthand(value: &str) -> Option<BorderShorthand> {
+    parse_shorthand(value, true)
+}
+
+fn parse_shorthand(value: &str, outline: bool) -> Option<BorderShorthand> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    let (mut width, mut style, mut color) = (None, None, None);
+    for token in split_top_level(value, ' ') {
+        let lower = token.to_ascii_lowercase();
+        if BORDER_STYLES.contains(&lower.as_str()) || (outline && lower == "auto") {
+            if style.replace(lower).is_some() { return None; }
+        } else if let Some(w) = border_width_token(&lower) {
+            if width.replace(w).is_some() { return None; }
+        } else if matches!(CssValue::parse(&lower), CssValue::Length(..)) {
+            // A negative width is invalid, not a color
+            return None;
+        } else if color.replace(token).is_some() {
+            return None;
+        }
+    }
+    // The specified width is kept for `none`/`hidden` too: only the used width is 0, and a later
+    // `border-style` brings it back. Renderers apply that rule.
+    Some(BorderShorthand {
+        width: width.unwrap_or_else(|| "3px".to_string()),
+        style: style.unwrap_or_else(|| "none".to_string()),
+        color: color.unwrap_or_else(|| "currentColor".to_string()),
+    })
+}
+
+fn border_width_token(token: &str) -> Option<String> {
+    match token {
+        "thin" => return Some("1px".to_string()),
+        "medium" => return Some("3px".to_string()),
+        "thick" => return Some("5px".to_string()),
+        "0" => return Some("0px".to_string()),
+        _ => {}
+    }
+    match CssValue::parse(token) {
+        CssValue::Length(n, _) if n >= 0.0 => Some(token.to_string()),
+        _ => None,
+    }
+}
+
+/// Expand a kebab-case border shorthand property (`border`, `border-top`, ..., `outline`)
+/// into kebab-case longhands. Returns `None` for other properties or unparseable values.
+pub fn expand_border_shorthand(prop: &str, value: &str) -> Option<Vec<(String, String)>> {
+    match prop {
+        "border" | "border-top" | "border-right" | "border-bottom" | "border-left" | "border-inline-start" | "border-inline-end"
+        | "outline" => {}
+        _ => return None,
+    }
+    let parsed = if prop == "outline" { parse_outline_shorthand(value)? } else { parse_border_shorthand(value)? };
+    Some(vec![
+        (format!("{}-width", prop), parsed.width),
+        (format!("{}-style", prop), parsed.style),
+        (format!("{}-color", prop), parsed.color),
+    ])
+}
@@ -0,0 +1,118 @@
+use indexmap::IndexMap;
+use serde_json::json;
+use crate::{get_tailwind_color_with_vars, CssProps};
+
+/// Selector suffix for utilities that style the children of an element rather
+/// than the element itself (`.space-x-4 > * + *`).
+pub const CHILD_SELECTOR_SUFFIX: &str = " > * + *";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+/// A parsed `space-*` / `divide-*` utility.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChildUtility {
+    /// Gap between children along an axis, in px (dp on Android)
+    Space(Axis, i32),
+    SpaceReverse(Axis),
+    /// Divider width between children along an axis, in px (dp on Android)
+    DivideWidth(Axis, i32),
+    DivideReverse(Axis),
+    DivideStyle(String),
+    DivideColor(String),
+}
+
+pub fn parse_child_utility(class: &str, vars: &IndexMap<String, String>) -> Option<ChildUtility> {
+    for (prefix, axis) in [("space-x", Axis::X), ("space-y", Axis::Y)] {
+        if let Some(rest) = class.strip_prefix(prefix) {
+            return match rest {
+                "-reverse" => Some(ChildUtility::SpaceReverse(axis)),
+                "-px" => Some(ChildUtility::Space(axis, 1)),
+                _ => rest.strip_prefix('-')?.parse::<i32>().ok().map(|n| ChildUtility::Space(axis, n * 4)),
+            };
+        }
+    }
+    for (prefix, axis) in [("divide-x", Axis::X), ("divide-y", Axis::Y)] {
+        if let Some(rest) = class.strip_prefix(prefix) {
+            return match rest {
+                "" => Some(ChildUtility::DivideWidth(axis, 1)),
+                "-reverse" => Some(ChildUtility::DivideReverse(axis)),
+                _ => rest.strip_prefix('-')?.parse::<i32>().ok().map(|n| ChildUtility::DivideWidth(axis, n)),
+            };
+        }
+    }
+    let rest = class.strip_prefix("divide-")?;
+    match rest {
+        "solid" | "dashed" | "dotted" | "double" | "none" => return Some(ChildUtility::DivideStyle(rest.to_string())),
+        _ => {}
+    }
+    if let Some(value) = rest.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return Some(ChildUtility::DivideColor(value.to_string()));
+    }
+    get_tailwind_color_with_vars(rest, vars).map(ChildUtility::DivideColor)
+}
+
+impl ChildUtility {
+    /// Web declarations for the `> * + *` child rule.
+    /// Mirrors Tailwind: the reverse flag swaps which side of each child receives the space/border.
+    pub fn web_props(&self) -> CssProps {
+        let mut p = CssProps::new();
+        match self {
+            ChildUtility::Space(axis, px) => {
+                let (var, before, after) = match axis {
+                    Axis::X => ("--tw-space-x-reverse", "margin-left", "margin-right"),
+                    Axis::Y => ("--tw-space-y-reverse", "margin-top", "margin-bottom"),
+                };
+                p.insert(var.into(), json!("0").into());
+                p.insert(after.into(), json!(format!("calc({}px * var({}))", px, var)).into());
+                p.insert(before.into(), json!(format!("calc({}px * calc(1 - var({})))", px, var)).into());
+            }
+            ChildUtility::SpaceReverse(axis) => {
+                let var = if *axis == Axis::X { "--tw-space-x-reverse" } else { "--tw-space-y-reverse" };
+                p.insert(var.into(), json!("1").into());
+            }
+            ChildUtility::DivideWidth(axis, px) => {
+                let (var, before, after) = match axis {
+                    Axis::X => ("--tw-divide-x-reverse", "border-left-width", "border-right-width"),
+                    Axis::Y => ("--tw-divide-y-reverse", "border-top-width", "border-bottom-width"),
+                };
+                p.insert(var.into(), json!("0").into());
+                p.insert(after.into(), json!(format!("calc({}px * var({}))", px, var)).into());
+                p.insert(before.into(), json!(format!("calc({}px * calc(1 - var({})))", px, var)).into());
+                p.insert("border-style".into(), json!("solid").into());
+            }
+            ChildUtility::DivideReverse(axis) => {
+                let var = if *axis == Axis::X { "--tw-divide-x-reverse" } else { "--tw-divide-y-reverse" };
+                p.insert(var.into(), json!("1").into());
+            }
+            ChildUtility::DivideStyle(style) => {
+                p.insert("border-style".into(), json!(style).into());
+            }
+            ChildUtility::DivideColor(color) => {
+                p.insert("border-color".into(), json!(color).into());
+            }
+        }
+        p
+    }
+
+    /// Parent-level Android hints; the renderer inserts the spacing/divider between children.
+    /// Sizes are emitted in dp and converted to px with the other dimension props.
+    pub fn apply_android_hints(&self, out: &mut IndexMap<String, serde_json::Value>, vars: &IndexMap<String, String>) {
+        match self {
+            ChildUtility::Space(Axis::X, px) => { out.insert("androidChildSpacingHorizontal".into(), json!(px)); }
+            ChildUtility::Space(Axis::Y, px) => { out.insert("androidChildSpacingVertical".into(), json!(px)); }
+            ChildUtility::SpaceReverse(_) => { out.insert("androidChildSpacingReverse".into(), json!(true)); }
+            ChildUtility::DivideWidth(Axis::X, px) => { out.insert("androidDividerHorizontal".into(), json!(px)); }
+            ChildUtility::DivideWidth(Axis::Y, px) => { out.insert("androidDividerVertical".into(), json!(px)); }
+            ChildUtility::DivideReverse(_) => { out.insert("androidDividerReverse".into(), json!(true)); }
+            ChildUtility::DivideStyle(style) => { out.insert("androidDividerStyle".into(), json!(style)); }
+            ChildUtility::DivideColor(color) => {
+                let resolved = crate::color::resolve_color(&crate::resolve_vars(color, vars), None, vars);
+                out.insert("androidDividerColor".into(), json!(resolved));
+            }
+        }
+    }
+}
@@ -1,5 +1,4 @@
 use indexmap::IndexMap;
-use serde_json::json;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
@@ -10,8 +9,15 @@ pub struct Color {
 }
 
 impl Color {
+    pub const BLACK: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 };
+    pub const WHITE: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+
     pub fn from_hex(hex: &str) -> Option<Self> {
         let hex = hex.trim_start_matches('#');
+        // Byte slicing below needs ASCII; from_str_radix alone would also accept a sign
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
         match hex.len() {
             3 => {
                 let r = u8::from_str_radix(&hex[0..1].repeat(2), 16).ok()? as f32 / 255.0;
@@ -43,22 +49,377 @@ impl Color {
         }
     }
 
-    pub fn to_hex(&self) -> String {
-        if self.a >= 1.0 {
-            format!("#{:02x}{:02x}{:02x}", (self.r * 255.0) as u8, (self.g * 255.0) as u8, (self.b * 255.0) as u8)
+    pub fn to_hex(self) -> String {
+        let [r, g, b, a] = self.to_rgba8();
+        if a == 255 {
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+        }
+    }
+
+    /// Android `Color.parseColor` form: `#RRGGBB` when opaque, otherwise `#AARRGGBB`
+    /// (alpha first, unlike CSS).
+    pub fn to_android_hex(self) -> String {
+        let [r, g, b, a] = self.to_rgba8();
+        if a == 255 {
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", a, r, g, b)
+        }
+    }
+
+    pub fn to_rgba8(self) -> [u8; 4] {
+        let q = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [q(self.r), q(self.g), q(self.b), q(self.a)]
+    }
+
+    /// Parse any CSS Color 4 value: hex, named colors, `transparent`, and the
+    /// `rgb()`, `hsl()`, `hwb()`, `lab()`, `lch()`, `oklab()`, `oklch()` and `color()` functions.
+    /// Out-of-gamut results are clamped to sRGB. `currentColor` and `var()` must be resolved by the caller.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        if value.starts_with('#') {
+            return Color::from_hex(&value);
+        }
+        if value == "transparent" {
+            return Some(Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 });
+        }
+        if let Some(hex) = named_color(&value) {
+            return Color::from_hex(hex);
+        }
+        let open = value.find('(')?;
+        let inner = value[open + 1..].strip_suffix(')')?;
+        let (components, alpha) = split_components(inner)?;
+        let name = &value[..open];
+        let alpha = match alpha {
+            Some(a) => parse_alpha(&a)?,
+            None => 1.0,
+        };
+        let c = |i: usize| components.get(i).map(|s| s.as_str());
+        let color = match name {
+            "rgb" | "rgba" => {
+                if components.len() != 3 { return None; }
+                let ch = |s: &str| parse_number_or_percent(s, 255.0).map(|v| v / 255.0);
+                Color { r: ch(c(0)?)?, g: ch(c(1)?)?, b: ch(c(2)?)?, a: alpha }
+            }
+            "hsl" | "hsla" => {
+                if components.len() != 3 { return None; }
+                let h = parse_hue(c(0)?)?;
+                let s = parse_number_or_percent(c(1)?, 100.0)? / 100.0;
+                let l = parse_number_or_percent(c(2)?, 100.0)? / 100.0;
+                Color::from_hsl(h, s, l, alpha)
+            }
+            "hwb" => {
+                if components.len() != 3 { return None; }
+                let h = parse_hue(c(0)?)?;
+                let w = parse_number_or_percent(c(1)?, 100.0)? / 100.0;
+                let b = parse_number_or_percent(c(2)?, 100.0)? / 100.0;
+                Color::from_hwb(h, w, b, alpha)
+            }
+            "lab" => {
+                if components.len() != 3 { return None; }
+                let l = parse_number_or_percent(c(0)?, 100.0)?;
+                let a = parse_number_or_percent(c(1)?, 125.0)?;
+                let b = parse_number_or_percent(c(2)?, 125.0)?;
+                Color::from_lab(l, a, b, alpha)
+            }
+            "lch" => {
+                if components.len() != 3 { return None; }
+                let l = parse_number_or_percent(c(0)?, 100.0)?;
+                let ch = parse_number_or_percent(c(1)?, 150.0)?;
+                let h = parse_hue(c(2)?)?.to_radians();
+                Color::from_lab(l, ch * h.cos(), ch * h.sin(), alpha)
+            }
+            "oklab" => {
+                if components.len() != 3 { return None; }
+                let l = parse_number_or_percent(c(0)?, 1.0)?;
+                let a = parse_number_or_percent(c(1)?, 0.4)?;
+                let b = parse_number_or_percent(c(2)?, 0.4)?;
+                Color::from_oklab(l, a, b, alpha)
+            }
+            "oklch" => {
+                if components.len() != 3 { return None; }
+                let l = parse_number_or_percent(c(0)?, 1.0)?;
+                let ch = parse_number_or_percent(c(1)?, 0.4)?;
+                let h = parse_hue(c(2)?)?.to_radians();
+                Color::from_oklab(l, ch * h.cos(), ch * h.sin(), alpha)
+            }
+            "color" => {
+                if components.len() != 4 { return None; }
+                let ch = |s: &str| parse_number_or_percent(s, 1.0);
+                let (r, g, b) = (ch(c(1)?)?, ch(c(2)?)?, ch(c(3)?)?);
+                match c(0)? {
+                    "srgb" => Color { r, g, b, a: alpha },
+                    "srgb-linear" => Color::from_linear_srgb(r, g, b, alpha),
+                    "display-p3" => {
+                        let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+                        Color::from_linear_srgb(
+                            1.224_940_1 * r - 0.224_940_4 * g,
+                            -0.042_056_9 * r + 1.042_057_1 * g,
+                            -0.019_637_6 * r - 0.078_636_1 * g + 1.098_273_5 * b,
+                            alpha,
+                        )
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        Some(color.clamped())
+    }
+
+    fn clamped(self) -> Self {
+        Color { r: self.r.clamp(0.0, 1.0), g: self.g.clamp(0.0, 1.0), b: self.b.clamp(0.0, 1.0), a: self.a.clamp(0.0, 1.0) }
+    }
+
+    /// `h` in degrees, `s`/`l` in 0..=1
+    pub fn from_hsl(h: f32, s: f32, l: f32, a: f32) -> Self {
+        let s = s.clamp(0.0, 1.0);
+        let l = l.clamp(0.0, 1.0);
+        let f = |n: f32| {
+            let k = (n + h.rem_euclid(360.0) / 30.0) % 12.0;
+            let amp = s * l.min(1.0 - l);
+            l - amp * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        Color { r: f(0.0), g: f(8.0), b: f(4.0), a }
+    }
+
+    /// Composite this color over an opaque `backdrop` (source-over)
+    pub fn over(self, backdrop: Color) -> Color {
+        let blend = |f: f32, b: f32| f * self.a + b * (1.0 - self.a);
+        Color { r: blend(self.r, backdrop.r), g: blend(self.g, backdrop.g), b: blend(self.b, backdrop.b), a: 1.0 }
+    }
+
+    /// Returns (hue degrees, saturation 0..=1, lightness 0..=1)
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        if d <= f32::EPSILON {
+            return (0.0, 0.0, l);
+        }
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == self.r {
+            60.0 * ((self.g - self.b) / d).rem_euclid(6.0)
+        } else if max == self.g {
+            60.0 * ((self.b - self.r) / d + 2.0)
         } else {
-            format!("#{:02x}{:02x}{:02x}{:02x}", (self.r * 255.0) as u8, (self.g * 255.0) as u8, (self.b * 255.0) as u8, (self.a * 255.0) as u8)
+            60.0 * ((self.r - self.g) / d + 4.0)
+        };
+        (h, s, l)
+    }
+
+    pub fn from_hwb(h: f32, w: f32, b: f32, a: f32) -> Self {
+        if w + b >= 1.0 {
+            let gray = w / (w + b);
+            return Color { r: gray, g: gray, b: gray, a };
         }
+        let base = Color::from_hsl(h, 1.0, 0.5, a);
+        let f = |v: f32| v * (1.0 - w - b) + w;
+        Color { r: f(base.r), g: f(base.g), b: f(base.b), a }
+    }
+
+    pub fn from_linear_srgb(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Color { r: linear_to_srgb(r), g: linear_to_srgb(g), b: linear_to_srgb(b), a }
+    }
+
+    pub fn to_linear_srgb(self) -> (f32, f32, f32) {
+        (srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b))
+    }
+
+    /// CIE Lab (D50), as used by CSS `lab()`/`lch()`
+    pub fn from_lab(l: f32, a: f32, b: f32, alpha: f32) -> Self {
+        const K: f32 = 24389.0 / 27.0;
+        const E: f32 = 216.0 / 24389.0;
+        let fy = (l + 16.0) / 116.0;
+        let fx = a / 500.0 + fy;
+        let fz = fy - b / 200.0;
+        let finv = |f: f32| if f * f * f > E { f * f * f } else { (116.0 * f - 16.0) / K };
+        let x = finv(fx) * 0.964_22;
+        let y = if l > K * E { fy * fy * fy } else { l / K };
+        let z = finv(fz) * 0.825_21;
+        // Bradford-adapted XYZ D50 -> linear sRGB
+        Color::from_linear_srgb(
+            3.134_136 * x - 1.617_386 * y - 0.490_662 * z,
+            -0.978_795 * x + 1.916_254 * y + 0.033_443 * z,
+            0.071_955 * x - 0.228_977 * y + 1.405_386 * z,
+            alpha,
+        )
+    }
+
+    /// Returns CIE Lab (D50) as (L 0..=100, a, b)
+    pub fn to_lab(self) -> (f32, f32, f32) {
+        const K: f32 = 24389.0 / 27.0;
+        const E: f32 = 216.0 / 24389.0;
+        let (r, g, b) = self.to_linear_srgb();
+        // linear sRGB -> Bradford-adapted XYZ D50, normalized to the D50 white point
+        let x = (0.436_074_7 * r + 0.385_064_9 * g + 0.143_080_4 * b) / 0.964_22;
+        let y = 0.222_504_5 * r + 0.716_878_6 * g + 0.060_616_9 * b;
+        let z = (0.013_932_2 * r + 0.097_104_5 * g + 0.714_173_3 * b) / 0.825_21;
+        let f = |t: f32| if t > E { t.cbrt() } else { (K * t + 16.0) / 116.0 };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
     }
 
-    pub fn mix(c1: Color, c2: Color, weight: f32) -> Color {
+    pub fn from_oklab(l: f32, a: f32, b: f32, alpha: f32) -> Self {
+        let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+        let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+        let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+        let (l3, m3, s3) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+        Color::from_linear_srgb(
+            4.076_741_7 * l3 - 3.307_711_6 * m3 + 0.230_969_94 * s3,
+            -1.268_438 * l3 + 2.609_757_4 * m3 - 0.341_319_38 * s3,
+            -0.004_196_086_3 * l3 - 0.703_418_6 * m3 + 1.707_614_7 * s3,
+            alpha,
+        )
+    }
+
+    /// Returns (L 0..=1, a, b)
+    pub fn to_oklab(self) -> (f32, f32, f32) {
+        let (r, g, b) = self.to_linear_srgb();
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        (
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        )
+    }
+
+    /// Components in `space`, using the units of the `from_space` inverse:
+    /// hsl/hwb as (deg, 0..=1, 0..=1), lab/lch lightness 0..=100, oklab/oklch lightness 0..=1.
+    pub fn to_space(self, space: ColorSpace) -> [f32; 3] {
+        let polar = |l: f32, a: f32, b: f32| [l, (a * a + b * b).sqrt(), b.atan2(a).to_degrees().rem_euclid(360.0)];
+        match space {
+            ColorSpace::Srgb => [self.r, self.g, self.b],
+            ColorSpace::SrgbLinear => {
+                let (r, g, b) = self.to_linear_srgb();
+                [r, g, b]
+            }
+            ColorSpace::Hsl => {
+                let (h, s, l) = self.to_hsl();
+                [h, s, l]
+            }
+            ColorSpace::Hwb => {
+                let (h, _, _) = self.to_hsl();
+                [h, self.r.min(self.g).min(self.b), 1.0 - self.r.max(self.g).max(self.b)]
+            }
+            ColorSpace::Lab => {
+                let (l, a, b) = self.to_lab();
+                [l, a, b]
+            }
+            ColorSpace::Lch => {
+                let (l, a, b) = self.to_lab();
+                polar(l, a, b)
+            }
+            ColorSpace::Oklab => {
+                let (l, a, b) = self.to_oklab();
+                [l, a, b]
+            }
+            ColorSpace::Oklch => {
+                let (l, a, b) = self.to_oklab();
+                polar(l, a, b)
+            }
+        }
+    }
+
+    pub fn from_space(space: ColorSpace, c: [f32; 3], alpha: f32) -> Self {
+        let rect = |l: f32, ch: f32, h: f32| (l, ch * h.to_radians().cos(), ch * h.to_radians().sin());
+        let color = match space {
+            ColorSpace::Srgb => Color { r: c[0], g: c[1], b: c[2], a: alpha },
+            ColorSpace::SrgbLinear => Color::from_linear_srgb(c[0], c[1], c[2], alpha),
+            ColorSpace::Hsl => Color::from_hsl(c[0], c[1], c[2], alpha),
+            ColorSpace::Hwb => Color::from_hwb(c[0], c[1], c[2], alpha),
+            ColorSpace::Lab => Color::from_lab(c[0], c[1], c[2], alpha),
+            ColorSpace::Lch => {
+                let (l, a, b) = rect(c[0], c[1], c[2]);
+                Color::from_lab(l, a, b, alpha)
+            }
+            ColorSpace::Oklab => Color::from_oklab(c[0], c[1], c[2], alpha),
+            ColorSpace::Oklch => {
+                let (l, a, b) = rect(c[0], c[1], c[2]);
+                Color::from_oklab(l, a, b, alpha)
+            }
+        };
+        color.clamped()
+    }
+
+    /// Interpolate from `c1` (weight 0) to `c2` (weight 1) in `space`.
+    fn mix_in(space: ColorSpace, c1: Color, c2: Color, weight: f32, hue_method: HueMethod) -> Color {
         let w = weight.clamp(0.0, 1.0);
-        let w1 = 1.0 - w;
-        Color {
-            r: c1.r * w1 + c2.r * w,
-            g: c1.g * w1 + c2.g * w,
-            b: c1.b * w1 + c2.b * w,
-            a: c1.a * w1 + c2.a * w,
+        let alpha = c1.a * (1.0 - w) + c2.a * w;
+        let (mut a, mut b) = (c1.to_space(space), c2.to_space(space));
+        let hue = space.hue_index();
+        if let Some(h) = hue {
+            // Achromatic colors have a powerless hue; borrow the other color's
+            let achromatic = |c: &[f32; 3]| if space == ColorSpace::Hwb { c[1] + c[2] >= 1.0 } else { c[1].abs() < 1e-4 };
+            if achromatic(&a) && !achromatic(&b) { a[h] = b[h]; }
+            if achromatic(&b) && !achromatic(&a) { b[h] = a[h]; }
+            let diff = b[h] - a[h];
+            match hue_method {
+                HueMethod::Shorter if diff > 180.0 => a[h] += 360.0,
+                HueMethod::Shorter if diff < -180.0 => b[h] += 360.0,
+                HueMethod::Longer if diff > 0.0 && diff < 180.0 => a[h] += 360.0,
+                HueMethod::Longer if diff > -180.0 && diff < 0.0 => b[h] += 360.0,
+                HueMethod::Increasing if diff < 0.0 => b[h] += 360.0,
+                HueMethod::Decreasing if diff > 0.0 => a[h] += 360.0,
+                _ => {}
+            }
+        }
+        let mut out = [0.0; 3];
+        for i in 0..3 {
+            out[i] = if Some(i) == hue || alpha <= 0.0 {
+                a[i] * (1.0 - w) + b[i] * w
+            } else {
+                // premultiplied interpolation
+                (a[i] * c1.a * (1.0 - w) + b[i] * c2.a * w) / alpha
+            };
+        }
+        if let Some(h) = hue {
+            out[h] = out[h].rem_euclid(360.0);
+        }
+        Color::from_space(space, out, alpha)
+    }
+}
+
+/// Android output keys that carry a color. Only these are rewritten to Android hex order;
+/// every other string is left alone.
+pub const ANDROID_COLOR_PROPERTIES: &[&str] = &[
+    "color", "backgroundColor", "borderColor", "borderTopColor", "borderRightColor",
+    "borderBottomColor", "borderLeftColor", "borderStartColor", "borderEndColor", "outlineColor", "placeholderColor", "caretColor",
+    "accentColor", "textDecorationColor", "tintColor", "fill", "stroke",
+    "androidShadowSpotColor", "androidShadowAmbientColor", "androidDividerColor", "rippleColor",
+];
+
+/// Android output keys holding a list of objects with a `color` field.
+const ANDROID_COLOR_LISTS: &[&str] = &["androidShadows", "androidTextShadows"];
+
+/// Rewrite every known color property from CSS `#RRGGBBAA` to Android `#AARRGGBB`.
+/// Values that aren't concrete colors (unresolved `var()`, `inherit`, ...) pass through.
+pub fn normalize_android_colors(styles: &mut IndexMap<String, serde_json::Value>) {
+    let convert = |v: &mut serde_json::Value| {
+        if let Some(color) = v.as_str().and_then(Color::parse) {
+            *v = serde_json::Value::String(color.to_android_hex());
+        }
+    };
+    for key in ANDROID_COLOR_PROPERTIES {
+        if let Some(v) = styles.get_mut(*key) {
+            convert(v);
+        }
+    }
+    if let Some(serde_json::Value::Array(colors)) = styles.get_mut("androidGradient").and_then(|g| g.get_mut("colors")) {
+        colors.iter_mut().for_each(convert);
+    }
+    for key in ANDROID_COLOR_LISTS {
+        if let Some(serde_json::Value::Array(layers)) = styles.get_mut(*key) {
+            for layer in layers.iter_mut() {
+                if let Some(v) = layer.get_mut("color") {
+                    convert(v);
+                }
+            }
         }
     }
 }
@@ -69,102 +430,464 @@ pub fn resolve_color(
     vars: &IndexMap<String, String>,
 ) -> String {
     let value = value.trim();
-    
-    if value == "currentColor" {
-        return current_color.unwrap_or("#000000").to_string();
+    match evaluate_color(value, current_color, vars) {
+        Some(color) => color.to_hex(),
+        // Unresolved var(), keywords like "inherit", or invalid input pass through unchanged
+        None => value.to_string(),
     }
+}
 
-    if value == "transparent" {
-        return "#00000000".to_string();
+/// Evaluate a color expression: any CSS color, `currentColor`, `color-mix()`, relative colors
+/// (`rgb(from <color> r g b / 50%)`) and the theme helpers `lighten()`, `darken()`, `alpha()`
+/// and `contrast()`.
+pub fn evaluate_color(
+    value: &str,
+    current_color: Option<&str>,
+    vars: &IndexMap<String, String>,
+) -> Option<Color> {
+    let value = crate::resolve_vars(value.trim(), vars);
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("currentColor") {
+        return evaluate_color(current_color.unwrap_or("#000000"), None, vars);
     }
+    if let Some((name, args)) = split_function(value) {
+        let name = name.to_ascii_lowercase();
+        let eval = |s: &str| evaluate_color(s, current_color, vars);
+        match name.as_str() {
+            "color-mix" => return resolve_color_mix(args, current_color, vars),
+            "lighten" | "darken" => {
+                let (color, amount) = two_args(args)?;
+                let amount = parse_number_or_percent(&amount, 100.0)? / 100.0;
+                let amount = if name == "darken" { -amount } else { amount };
+                let c = eval(&color)?;
+                let (h, s, l) = c.to_hsl();
+                return Some(Color::from_hsl(h, s, l + amount, c.a));
+            }
+            "alpha" => {
+                let (color, alpha) = two_args(args)?;
+                let c = eval(&color)?;
+                return Some(Color { a: parse_alpha(&alpha)?, ..c });
+            }
+            "contrast" => {
+                let parts = crate::utils::split_top_level(args, ',');
+                let bg = eval(parts.first()?)?;
+                let (dark, light) = match parts.len() {
+                    1 => (Color::BLACK, Color::WHITE),
+                    3 => (eval(&parts[1])?, eval(&parts[2])?),
+                    _ => return None,
+                };
+                return Some(if contrast_ratio(bg, dark) >= contrast_ratio(bg, light) { dark } else { light });
+            }
+            _ => {}
+        }
+        if let Some(rest) = args.trim_start().strip_prefix("from ") {
+            return resolve_relative_color(&name, rest, current_color, vars);
+        }
+    }
+    Color::parse(value)
+}
 
-    if value == "black" {
-        return "#000000".to_string();
+/// Replace the non-standard theme helpers (`lighten()`, `darken()`, `alpha()`, `contrast()`)
+/// anywhere inside a value with hex colors, so browsers receive valid CSS.
+/// Standard CSS color syntax (including `color-mix()`) is left for the browser.
+pub fn resolve_color_functions(value: &str, vars: &IndexMap<String, String>) -> String {
+    let mut out = value.to_string();
+    for name in ["lighten(", "darken(", "alpha(", "contrast("] {
+        let mut search_from = 0;
+        while let Some(pos) = out[search_from..].find(name).map(|p| p + search_from) {
+            let boundary = out[..pos].chars().last().is_none_or(|c| !(c.is_ascii_alphanumeric() || c == '-'));
+            let end = matching_paren(&out, pos + name.len() - 1);
+            match (boundary, end) {
+                (true, Some(end)) => {
+                    let expr = out[pos..=end].to_string();
+                    let replacement = evaluate_color(&expr, None, vars).map(Color::to_hex).unwrap_or(expr);
+                    out.replace_range(pos..=end, &replacement);
+                    search_from = pos + replacement.len();
+                }
+                _ => search_from = pos + name.len(),
+            }
+        }
     }
+    out
+}
 
-    if value == "white" {
-        return "#ffffff".to_string();
+fn matching_paren(s: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, ch) in s[open..].char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
     }
+    None
+}
 
-    if value == "gray" || value == "grey" {
-        return "#808080".to_string();
+/// Split `name(args)` into its name and argument text.
+fn split_function(value: &str) -> Option<(&str, &str)> {
+    let open = value.find('(')?;
+    if matching_paren(value, open)? != value.len() - 1 {
+        return None;
+    }
+    let name = &value[..open];
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
     }
+    Some((name, &value[open + 1..value.len() - 1]))
+}
 
-    if value == "red" {
-        return "#ff0000".to_string();
+fn two_args(args: &str) -> Option<(String, String)> {
+    let mut parts = crate::utils::split_top_level(args, ',');
+    if parts.len() != 2 {
+        return None;
     }
+    let second = parts.pop()?;
+    Some((parts.pop()?, second))
+}
+
+/// WCAG 2.x relative luminance
+pub fn relative_luminance(c: Color) -> f32 {
+    let (r, g, b) = c.to_linear_srgb();
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// WCAG 2.x contrast ratio (1.0 ..= 21.0). Alpha is ignored.
+pub fn contrast_ratio(a: Color, b: Color) -> f32 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
 
-    if value == "green" {
-        return "#00ff00".to_string();
+/// CSS relative color syntax: `<fn>(from <origin> <c1> <c2> <c3> [/ <alpha>])`.
+/// Channel keywords take the origin's value in that function's space and may be used
+/// inside `calc()`.
+fn resolve_relative_color(
+    name: &str,
+    rest: &str,
+    current_color: Option<&str>,
+    vars: &IndexMap<String, String>,
+) -> Option<Color> {
+    let tokens = crate::utils::split_top_level(rest, ' ');
+    let (origin, components) = tokens.split_first()?;
+    let origin = evaluate_color(origin, current_color, vars)?;
+    let (keys, values): ([&str; 3], [f32; 3]) = match name {
+        "rgb" | "rgba" => (["r", "g", "b"], [origin.r * 255.0, origin.g * 255.0, origin.b * 255.0]),
+        "hsl" | "hsla" => {
+            let [h, s, l] = origin.to_space(ColorSpace::Hsl);
+            (["h", "s", "l"], [h, s * 100.0, l * 100.0])
+        }
+        "hwb" => {
+            let [h, w, b] = origin.to_space(ColorSpace::Hwb);
+            (["h", "w", "b"], [h, w * 100.0, b * 100.0])
+        }
+        "lab" => (["l", "a", "b"], origin.to_space(ColorSpace::Lab)),
+        "lch" => (["l", "c", "h"], origin.to_space(ColorSpace::Lch)),
+        "oklab" => (["l", "a", "b"], origin.to_space(ColorSpace::Oklab)),
+        "oklch" => (["l", "c", "h"], origin.to_space(ColorSpace::Oklch)),
+        _ => return None,
+    };
+    let channels = [(keys[0], values[0]), (keys[1], values[1]), (keys[2], values[2]), ("alpha", origin.a)];
+    let eval = |token: &str| -> Option<String> {
+        if let Some((_, v)) = channels.iter().find(|(k, _)| *k == token) {
+            return Some(v.to_string());
+        }
+        if let Some(expr) = token.strip_prefix("calc(").and_then(|t| t.strip_suffix(')')) {
+            return eval_channel_expr(expr, &channels).map(|v| v.to_string());
+        }
+        Some(token.to_string())
+    };
+    let (main, alpha) = match components.iter().position(|t| t == "/") {
+        Some(i) => (&components[..i], components.get(i + 1)),
+        None => (components, None),
+    };
+    if main.len() != 3 {
+        return None;
+    }
+    let mut rebuilt = Vec::with_capacity(3);
+    for token in main {
+        rebuilt.push(eval(token)?);
+    }
+    let alpha = match alpha {
+        Some(a) => eval(a)?,
+        None => origin.a.to_string(),
+    };
+    Color::parse(&format!("{}({} / {})", name, rebuilt.join(" "), alpha))
+}
+
+/// Evaluate `+ - * /` arithmetic over numbers and channel keywords.
+fn eval_channel_expr(expr: &str, channels: &[(&str, f32)]) -> Option<f32> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = expr.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if "+-*/()".contains(c) {
+            tokens.push(c.to_string());
+            i += 1;
+        } else {
+            let start = i;
+            while i < chars.len() && !chars[i].is_whitespace() && !"+-*/()".contains(chars[i]) {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        }
+    }
+    let mut pos = 0;
+    let value = parse_sum(&tokens, &mut pos, channels)?;
+    (pos == tokens.len()).then_some(value)
+}
+
+fn parse_sum(tokens: &[String], pos: &mut usize, channels: &[(&str, f32)]) -> Option<f32> {
+    let mut value = parse_product(tokens, pos, channels)?;
+    while let Some(op) = tokens.get(*pos).filter(|t| *t == "+" || *t == "-").cloned() {
+        *pos += 1;
+        let rhs = parse_product(tokens, pos, channels)?;
+        value = if op == "+" { value + rhs } else { value - rhs };
     }
+    Some(value)
+}
 
-    if value == "blue" {
-        return "#0000ff".to_string();
+fn parse_product(tokens: &[String], pos: &mut usize, channels: &[(&str, f32)]) -> Option<f32> {
+    let mut value = parse_factor(tokens, pos, channels)?;
+    while let Some(op) = tokens.get(*pos).filter(|t| *t == "*" || *t == "/").cloned() {
+        *pos += 1;
+        let rhs = parse_factor(tokens, pos, channels)?;
+        value = if op == "*" { value * rhs } else { value / rhs };
     }
+    Some(value)
+}
 
-    if value.starts_with("color-mix(") && value.ends_with(')') {
-        return resolve_color_mix(value, current_color, vars);
+fn parse_factor(tokens: &[String], pos: &mut usize, channels: &[(&str, f32)]) -> Option<f32> {
+    let token = tokens.get(*pos)?.clone();
+    *pos += 1;
+    match token.as_str() {
+        "-" => parse_factor(tokens, pos, channels).map(|v| -v),
+        "(" => {
+            let v = parse_sum(tokens, pos, channels)?;
+            (tokens.get(*pos)? == ")").then(|| *pos += 1)?;
+            Some(v)
+        }
+        t => channels.iter().find(|(k, _)| *k == t).map(|(_, v)| *v).or_else(|| t.parse::<f32>().ok()),
     }
+}
 
-    if value.starts_with('#') {
-        return value.to_string();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    Srgb,
+    SrgbLinear,
+    Lab,
+    Lch,
+    Oklab,
+    Oklch,
+    Hsl,
+    Hwb,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HueMethod {
+    Shorter,
+    Longer,
+    Increasing,
+    Decreasing,
+}
+
+impl ColorSpace {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "srgb" => ColorSpace::Srgb,
+            // XYZ is a linear transform of linear sRGB, so mixing gives identical results
+            "srgb-linear" | "xyz" | "xyz-d65" | "xyz-d50" => ColorSpace::SrgbLinear,
+            "lab" => ColorSpace::Lab,
+            "lch" => ColorSpace::Lch,
+            "oklab" => ColorSpace::Oklab,
+            "oklch" => ColorSpace::Oklch,
+            "hsl" => ColorSpace::Hsl,
+            "hwb" => ColorSpace::Hwb,
+            _ => return None,
+        })
     }
 
-    // Fallback to variable resolution if not already done
-    value.to_string()
+    /// Index of the hue component for polar spaces
+    fn hue_index(self) -> Option<usize> {
+        match self {
+            ColorSpace::Hsl | ColorSpace::Hwb => Some(0),
+            ColorSpace::Lch | ColorSpace::Oklch => Some(2),
+            _ => None,
+        }
+    }
 }
 
+/// color-mix(in <space> [<hue-method> hue], <color> [<pct>], <color> [<pct>])
+///
+/// Follows CSS Color 5: percentages are normalized, a sum below 100% scales the result's
+/// alpha, rectangular components are interpolated premultiplied, and hues take the
+/// shorter arc unless another method is given.
 fn resolve_color_mix(
-    value: &str,
+    content: &str,
     current_color: Option<&str>,
     vars: &IndexMap<String, String>,
-) -> String {
-    // color-mix(in srgb, color1 [percentage], color2 [percentage])
-    let content = &value[10..value.len() - 1];
-    let parts: Vec<&str> = content.split(',').map(|s| s.trim()).collect();
-    
-    if parts.len() < 3 {
-        return "#000000".to_string();
+) -> Option<Color> {
+    let parts = crate::utils::split_top_level(content, ',');
+    if parts.len() != 3 {
+        return None;
     }
-
     log::debug!("[resolve_color_mix] content={} current_color={:?}", content, current_color);
 
-    // Skip "in srgb"
-    let c1_part = parts[1];
-    let c2_part = parts[2];
-
-    let (c1_str, p1) = parse_color_and_percentage(c1_part);
-    let (c2_str, p2) = parse_color_and_percentage(c2_part);
-
-    let c1_hex = resolve_color(c1_str, current_color, vars);
-    let c2_hex = resolve_color(c2_str, current_color, vars);
-
-    log::debug!("[resolve_color_mix] c1_str={} -> c1_hex={}, c2_str={} -> c2_hex={}", c1_str, c1_hex, c2_str, c2_hex);
+    let spec: Vec<&str> = parts[0].split_whitespace().collect();
+    if spec.first() != Some(&"in") {
+        return None;
+    }
+    let space = ColorSpace::parse(spec.get(1)?)?;
+    let hue_method = match spec.get(2..) {
+        None | Some([]) => HueMethod::Shorter,
+        Some([method, "hue"]) => match *method {
+            "shorter" => HueMethod::Shorter,
+            "longer" => HueMethod::Longer,
+            "increasing" => HueMethod::Increasing,
+            "decreasing" => HueMethod::Decreasing,
+            _ => return None,
+        },
+        _ => return None,
+    };
 
-    let c1 = Color::from_hex(&c1_hex).unwrap_or(Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 });
-    let c2 = Color::from_hex(&c2_hex).unwrap_or(Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 });
+    let (c1_str, p1) = parse_color_and_percentage(&parts[1]);
+    let (c2_str, p2) = parse_color_and_percentage(&parts[2]);
+    let c1 = evaluate_color(c1_str, current_color, vars)?;
+    let c2 = evaluate_color(c2_str, current_color, vars)?;
 
-    let weight = if let Some(p) = p2 {
-        p / 100.0
-    } else if let Some(p) = p1 {
-        1.0 - (p / 100.0)
-    } else {
-        0.5
+    let (p1, p2) = match (p1, p2) {
+        (Some(a), Some(b)) => (a / 100.0, b / 100.0),
+        (Some(a), None) => (a / 100.0, 1.0 - a / 100.0),
+        (None, Some(b)) => (1.0 - b / 100.0, b / 100.0),
+        (None, None) => (0.5, 0.5),
     };
-
-    let result = Color::mix(c1, c2, weight).to_hex();
-    log::debug!("[resolve_color_mix] weight={} result={}", weight, result);
-    result
+    let sum = p1 + p2;
+    if sum <= 0.0 {
+        return None;
+    }
+    let weight = p2 / sum;
+    let mut result = Color::mix_in(space, c1, c2, weight, hue_method);
+    if sum < 1.0 {
+        result.a *= sum;
+    }
+    log::debug!("[resolve_color_mix] weight={} result={}", weight, result.to_hex());
+    Some(result)
 }
 
 fn parse_color_and_percentage(part: &str) -> (&str, Option<f32>) {
-    let parts: Vec<&str> = part.split_whitespace().collect();
-    if parts.len() == 2 {
-        if let Some(p) = parts[1].strip_suffix('%') {
+    let part = part.trim();
+    for (color, pct) in [part.rsplit_once(char::is_whitespace), part.split_once(char::is_whitespace).map(|(p, c)| (c, p))].into_iter().flatten() {
+        if let Some(p) = pct.strip_suffix('%') {
             if let Ok(val) = p.parse::<f32>() {
-                return (parts[0], Some(val));
+                return (color.trim(), Some(val));
             }
         }
     }
     (part, None)
 }
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c.abs() <= 0.040_45 { c / 12.92 } else { c.signum() * ((c.abs() + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c.abs() <= 0.003_130_8 { c * 12.92 } else { c.signum() * (1.055 * c.abs().powf(1.0 / 2.4) - 0.055) }
+}
+
+/// Split function arguments into color components and an optional alpha.
+/// Accepts both the legacy comma syntax (`rgb(1, 2, 3, 0.5)`) and the modern
+/// space syntax (`rgb(1 2 3 / 50%)`).
+fn split_components(inner: &str) -> Option<(Vec<String>, Option<String>)> {
+    if inner.contains(',') {
+        let mut parts: Vec<String> = inner.split(',').map(|s| s.trim().to_string()).collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return None;
+        }
+        let alpha = if parts.len() == 4 { parts.pop() } else { None };
+        return Some((parts, alpha));
+    }
+    let (main, alpha) = match inner.split_once('/') {
+        Some((m, a)) => (m, Some(a.trim().to_string())),
+        None => (inner, None),
+    };
+    Some((main.split_whitespace().map(|s| s.to_string()).collect(), alpha))
+}
+
+/// A number, or a percentage of `percent_ref`. `none` is treated as zero.
+fn parse_number_or_percent(s: &str, percent_ref: f32) -> Option<f32> {
+    if s == "none" {
+        return Some(0.0);
+    }
+    if let Some(p) = s.strip_suffix('%') {
+        return p.trim().parse::<f32>().ok().map(|v| v / 100.0 * percent_ref);
+    }
+    s.parse::<f32>().ok()
+}
+
+fn parse_alpha(s: &str) -> Option<f32> {
+    parse_number_or_percent(s, 1.0).map(|a| a.clamp(0.0, 1.0))
+}
+
+/// Hue in degrees; accepts `deg`, `rad`, `grad`, `turn` or a bare number.
+fn parse_hue(s: &str) -> Option<f32> {
+    if s == "none" {
+        return Some(0.0);
+    }
+    for (suffix, factor) in [("deg", 1.0), ("grad", 0.9), ("rad", 180.0 / std::f32::consts::PI), ("turn", 360.0)] {
+        if let Some(n) = s.strip_suffix(suffix) {
+            return n.parse::<f32>().ok().map(|v| v * factor);
+        }
+    }
+    s.parse::<f32>().ok()
+}
+
+/// The 148 CSS named colors (CSS Color 4, including `rebeccapurple` and the gray/grey aliases).
+fn named_color(name: &str) -> Option<&'static str> {
+    NAMED_COLORS.binary_search_by(|(n, _)| n.cmp(&name)).ok().map(|i| NAMED_COLORS[i].1)
+}
+
+static NAMED_COLORS: &[(&str, &str)] = &[
+    ("aliceblue", "#f0f8ff"), ("antiquewhite", "#faebd7"), ("aqua", "#00ffff"), ("aquamarine", "#7fffd4"),
+    ("azure", "#f0ffff"), ("beige", "#f5f5dc"), ("bisque", "#ffe4c4"), ("black", "#000000"),
+    ("blanchedalmond", "#ffebcd"), ("blue", "#0000ff"), ("blueviolet", "#8a2be2"), ("brown", "#a52a2a"),
+    ("burlywood", "#deb887"), ("cadetblue", "#5f9ea0"), ("chartreuse", "#7fff00"), ("chocolate", "#d2691e"),
+    ("coral", "#ff7f50"), ("cornflowerblue", "#6495ed"), ("cornsilk", "#fff8dc"), ("crimson", "#dc143c"),
+    ("cyan", "#00ffff"), ("darkblue", "#00008b"), ("darkcyan", "#008b8b"), ("darkgoldenrod", "#b8860b"),
+    ("darkgray", "#a9a9a9"), ("darkgreen", "#006400"), ("darkgrey", "#a9a9a9"), ("darkkhaki", "#bdb76b"),
+    ("darkmagenta", "#8b008b"), ("darkolivegreen", "#556b2f"), ("darkorange", "#ff8c00"), ("darkorchid", "#9932cc"),
+    ("darkred", "#8b0000"), ("darksalmon", "#e9967a"), ("darkseagreen", "#8fbc8f"), ("darkslateblue", "#483d8b"),
+    ("darkslategray", "#2f4f4f"), ("darkslategrey", "#2f4f4f"), ("darkturquoise", "#00ced1"), ("darkviolet", "#9400d3"),
+    ("deeppink", "#ff1493"), ("deepskyblue", "#00bfff"), ("dimgray", "#696969"), ("dimgrey", "#696969"),
+    ("dodgerblue", "#1e90ff"), ("firebrick", "#b22222"), ("floralwhite", "#fffaf0"), ("forestgreen", "#228b22"),
+    ("fuchsia", "#ff00ff"), ("gainsboro", "#dcdcdc"), ("ghostwhite", "#f8f8ff"), ("gold", "#ffd700"),
+    ("goldenrod", "#daa520"), ("gray", "#808080"), ("green", "#008000"), ("greenyellow", "#adff2f"),
+    ("grey", "#808080"), ("honeydew", "#f0fff0"), ("hotpink", "#ff69b4"), ("indianred", "#cd5c5c"),
+    ("indigo", "#4b0082"), ("ivory", "#fffff0"), ("khaki", "#f0e68c"), ("lavender", "#e6e6fa"),
+    ("lavenderblush", "#fff0f5"), ("lawngreen", "#7cfc00"), ("lemonchiffon", "#fffacd"), ("lightblue", "#add8e6"),
+    ("lightcoral", "#f08080"), ("lightcyan", "#e0ffff"), ("lightgoldenrodyellow", "#fafad2"), ("lightgray", "#d3d3d3"),
+    ("lightgreen", "#90ee90"), ("lightgrey", "#d3d3d3"), ("lightpink", "#ffb6c1"), ("lightsalmon", "#ffa07a"),
+    ("lightseagreen", "#20b2aa"), ("lightskyblue", "#87cefa"), ("lightslategray", "#778899"), ("lightslategrey", "#778899"),
+    ("lightsteelblue", "#b0c4de"), ("lightyellow", "#ffffe0"), ("lime", "#00ff00"), ("limegreen", "#32cd32"),
+    ("linen", "#faf0e6"), ("magenta", "#ff00ff"), ("maroon", "#800000"), ("mediumaquamarine", "#66cdaa"),
+    ("mediumblue", "#0000cd"), ("mediumorchid", "#ba55d3"), ("mediumpurple", "#9370db"), ("mediumseagreen", "#3cb371"),
+    ("mediumslateblue", "#7b68ee"), ("mediumspringgreen", "#00fa9a"), ("mediumturquoise", "#48d1cc"), ("mediumvioletred", "#c71585"),
+    ("midnightblue", "#191970"), ("mintcream", "#f5fffa"), ("mistyrose", "#ffe4e1"), ("moccasin", "#ffe4b5"),
+    ("navajowhite", "#ffdead"), ("navy", "#000080"), ("oldlace", "#fdf5e6"), ("olive", "#808000"),
+    ("olivedrab", "#6b8e23"), ("orange", "#ffa500"), ("orangered", "#ff4500"), ("orchid", "#da70d6"),
+    ("palegoldenrod", "#eee8aa"), ("palegreen", "#98fb98"), ("paleturquoise", "#afeeee"), ("palevioletred", "#db7093"),
+    ("papayawhip", "#ffefd5"), ("peachpuff", "#ffdab9"), ("peru", "#cd853f"), ("pink", "#ffc0cb"),
+    ("plum", "#dda0dd"), ("powderblue", "#b0e0e6"), ("purple", "#800080"), ("rebeccapurple", "#663399"),
+    ("red", "#ff0000"), ("rosybrown", "#bc8f8f"), ("royalblue", "#4169e1"), ("saddlebrown", "#8b4513"),
+    ("salmon", "#fa8072"), ("sandybrown", "#f4a460"), ("seagreen", "#2e8b57"), ("seashell", "#fff5ee"),
+    ("sienna", "#a0522d"), ("silver", "#c0c0c0"), ("skyblue", "#87ceeb"), ("slateblue", "#6a5acd"),
+    ("slategray", "#708090"), ("slategrey", "#708090"), ("snow", "#fffafa"), ("springgreen", "#00ff7f"),
+    ("steelblue", "#4682b4"), ("tan", "#d2b48c"), ("teal", "#008080"), ("thistle", "#d8bfd8"),
+    ("tomato", "#ff6347"), ("turquoise", "#40e0d0"), ("violet", "#ee82ee"), ("wheat", "#f5deb3"),
+    ("white", "#ffffff"), ("whitesmoke", "#f5f5f5"), ("yellow", "#ffff00"), ("yellowgreen", "#9acd32"),
+];
@@ -0,0 +1,142 @@
+use indexmap::IndexMap;
+use serde::Serialize;
+use crate::color::{contrast_ratio, evaluate_color, Color};
+use crate::value::{CssValue, LengthUnit};
+use crate::{CssProps, SelectorStyles};
+
+/// WCAG 2.x minimum contrast ratios
+pub const AA_NORMAL: f32 = 4.5;
+pub const AA_LARGE: f32 = 3.0;
+pub const AAA_NORMAL: f32 = 7.0;
+pub const AAA_LARGE: f32 = 4.5;
+
+/// Where a checked foreground/background pair came from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PairSource {
+    /// A `contrast_pairs` entry: foreground variable -> background variable
+    Variables { foreground: String, background: String },
+    /// A selector's `color` (or `placeholder-color`) against its `background-color`
+    Selector { selector: String, property: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContrastCheck {
+    pub source: PairSource,
+    /// Resolved colors as hex; translucent colors are composited before measuring
+    pub foreground: String,
+    pub background: String,
+    /// Contrast ratio rounded to two decimals
+    pub ratio: f32,
+    /// Large text (>= 24px, or >= 18.66px bold) uses the relaxed thresholds
+    pub large_text: bool,
+    pub aa: bool,
+    pub aaa: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThemeContrastReport {
+    pub theme: String,
+    pub checks: Vec<ContrastCheck>,
+}
+
+impl ThemeContrastReport {
+    pub fn aa_failures(&self) -> Vec<&ContrastCheck> {
+        self.checks.iter().filter(|c| !c.aa).collect()
+    }
+
+    pub fn aaa_failures(&self) -> Vec<&ContrastCheck> {
+        self.checks.iter().filter(|c| !c.aaa).collect()
+    }
+}
+
+/// Check every declared variable pair and every selector that sets both a text color and a
+/// background color. Pairs whose colors can't be resolved are skipped.
+pub fn audit_theme(
+    theme: &str,
+    selectors: &SelectorStyles,
+    vars: &IndexMap<String, String>,
+    pairs: &IndexMap<String, String>,
+) -> ThemeContrastReport {
+    let mut checks = Vec::new();
+    for (fg_var, bg_var) in pairs {
+        let fg = vars.get(fg_var).and_then(|v| evaluate_color(v, None, vars));
+        let bg = vars.get(bg_var).and_then(|v| evaluate_color(v, None, vars));
+        if let (Some(fg), Some(bg)) = (fg, bg) {
+            let source = PairSource::Variables { foreground: fg_var.clone(), background: bg_var.clone() };
+            checks.push(check(source, fg, bg, false));
+        }
+    }
+    for (selector, props) in selectors {
+        // `input::placeholder` inherits its background from `input` unless it sets its own
+        let base = selector.strip_suffix("::placeholder");
+        let bg_props = match base.and_then(|b| selectors.get(b)) {
+            Some(base_props) if prop(props, "background-color").is_none() => base_props,
+            _ => props,
+        };
+        let text_color = prop(props, "color");
+        let Some(bg) = prop(bg_props, "background-color").and_then(|v| evaluate_color(v, text_color, vars)) else {
+            continue;
+        };
+        let large = is_large_text(props);
+        for property in ["color", "placeholder-color"] {
+            if let Some(fg) = prop(props, property).and_then(|v| evaluate_color(v, text_color, vars)) {
+                let property = if base.is_some() { "placeholder-color" } else { property };
+                let source = PairSource::Selector { selector: selector.clone(), property: property.to_string() };
+                checks.push(check(source, fg, bg, large));
+            }
+        }
+    }
+    ThemeContrastReport { theme: theme.to_string(), checks }
+}
+
+fn check(source: PairSource, fg: Color, bg: Color, large_text: bool) -> ContrastCheck {
+    // Unknown backdrop: assume a white canvas behind a translucent background
+    let bg = bg.over(Color::WHITE);
+    let fg = fg.over(bg);
+    let ratio = (contrast_ratio(fg, bg) * 100.0).round() / 100.0;
+    let (aa, aaa) = if large_text { (AA_LARGE, AAA_LARGE) } else { (AA_NORMAL, AAA_NORMAL) };
+    ContrastCheck {
+        source,
+        foreground: fg.to_hex(),
+        background: bg.to_hex(),
+        ratio,
+        large_text,
+        aa: ratio >= aa,
+        aaa: ratio >= aaa,
+    }
+}
+
+/// Look up a property by its kebab-case name, accepting the camelCase spelling too.
+fn prop<'a>(props: &'a CssProps, kebab: &str) -> Option<&'a str> {
+    props
+        .get(kebab)
+        .or_else(|| props.get(&crate::camel_case(kebab)))
+        .and_then(|v| v.as_str())
+}
+
+fn is_large_text(props: &CssProps) -> bool {
+    let size = props
+        .get("font-size")
+        .or_else(|| props.get("fontSize"))
+        .and_then(|v| {
+            // em has no parent size here, so like rem it is taken against the 16px default;
+            // viewport units and unknown units can't be judged
+            CssValue::from_json(v).length_with(|n, unit| match unit {
+                LengthUnit::Px | LengthUnit::Dp | LengthUnit::Sp => Some(n),
+                LengthUnit::Pt => Some(n * 4.0 / 3.0),
+                LengthUnit::Rem | LengthUnit::Em => Some(n * 16.0),
+                _ => None,
+            })
+        })
+        .map(|px| px as f32);
+    let bold = match props.get("font-weight").or_else(|| props.get("fontWeight")).map(|v| v.raw()) {
+        Some(serde_json::Value::Number(n)) => n.as_f64().is_some_and(|w| w >= 700.0),
+        Some(serde_json::Value::String(s)) => s == "bold" || s == "bolder" || s.parse::<f32>().is_ok_and(|w| w >= 700.0),
+        _ => false,
+    };
+    match size {
+        Some(px) => px >= 24.0 || (bold && px >= 18.66),
+        None => false,
+    }
+}
@@ -0,0 +1,87 @@
+use indexmap::IndexMap;
+use crate::color::{contrast_ratio, evaluate_color, Color, ColorSpace};
+use crate::utils::resolve_vars;
+use crate::ThemeEntry;
+
+/// Derive a dark counterpart of a theme.
+///
+/// `vars` and `pairs` are the source's effective variables and contrast pairs. Every variable
+/// holding a literal color is remapped by inverting its OKLCH lightness (chroma, hue and alpha are
+/// kept); variables that reference other variables follow their targets. Foregrounds in `pairs`
+/// are then pushed away from their background until they reach `min_contrast`, with both sides
+/// resolved through references against the remapped colors.
+/// The result only carries the remapped variables and inherits everything else from `source_key`.
+pub fn derive_dark_theme(
+    source_key: &str,
+    source: &ThemeEntry,
+    vars: &IndexMap<String, String>,
+    pairs: &IndexMap<String, String>,
+    min_contrast: f32,
+) -> ThemeEntry {
+    let mut dark: IndexMap<String, Color> = IndexMap::new();
+    for (key, value) in vars {
+        if value.contains("var(") {
+            continue;
+        }
+        if let Some(color) = evaluate_color(value, None, vars) {
+            dark.insert(key.clone(), invert_lightness(color));
+        }
+    }
+
+    for (fg_key, bg_key) in pairs {
+        // Both sides are judged as the dark theme will render them, references included
+        let resolved = dark_variables(vars, &dark);
+        let color_of = |key: &str| resolved.get(key).and_then(|v| evaluate_color(v, None, &resolved));
+        if let (Some(fg), Some(bg)) = (color_of(fg_key), color_of(bg_key)) {
+            let adjusted = ensure_contrast(fg, bg.over(Color::WHITE), min_contrast);
+            // A referenced foreground keeps following its target unless it has to change
+            if dark.contains_key(fg_key) || adjusted != fg {
+                dark.insert(fg_key.clone(), adjusted);
+            }
+        }
+    }
+
+    ThemeEntry {
+        name: Some(format!("{} Dark", source.name.as_deref().unwrap_or(source_key))),
+        inherits: Some(source_key.to_string()),
+        variables: dark.into_iter().map(|(k, c)| (k, c.to_hex())).collect(),
+        ..Default::default()
+    }
+}
+
+/// The source variables as the dark theme sees them: remapped colors replaced, and references
+/// followed through (a few levels deep) so they land on the remapped values.
+fn dark_variables(vars: &IndexMap<String, String>, dark: &IndexMap<String, Color>) -> IndexMap<String, String> {
+    let mut resolved: IndexMap<String, String> =
+        vars.iter().map(|(k, v)| (k.clone(), dark.get(k).map_or_else(|| v.clone(), |c| c.to_hex()))).collect();
+    for _ in 0..8 {
+        let next: IndexMap<String, String> =
+            resolved.iter().map(|(k, v)| (k.clone(), if v.contains("var(") { resolve_vars(v, &resolved) } else { v.clone() })).collect();
+        if next == resolved {
+            break;
+        }
+        resolved = next;
+    }
+    resolved
+}
+
+fn invert_lightness(color: Color) -> Color {
+    let [l, c, h] = color.to_space(ColorSpace::Oklch);
+    Color::from_space(ColorSpace::Oklch, [1.0 - l.clamp(0.0, 1.0), c, h], color.a)
+}
+
+/// Move `fg` lightness away from `bg` in small OKLCH steps until the contrast target is met
+/// (or lightness runs out).
+fn ensure_contrast(fg: Color, bg: Color, min_contrast: f32) -> Color {
+    let [mut l, c, h] = fg.to_space(ColorSpace::Oklch);
+    let towards_light = contrast_ratio(bg, Color::WHITE) >= contrast_ratio(bg, Color::BLACK);
+    let mut adjusted = fg;
+    while contrast_ratio(adjusted.over(bg), bg) < min_contrast {
+        l = if towards_light { l + 0.01 } else { l - 0.01 };
+        if !(0.0..=1.0).contains(&l) {
+            break;
+        }
+        adjusted = Color::from_space(ColorSpace::Oklch, [l, c, h], fg.a);
+    }
+    adjusted
+}
@@ -9,6 +9,10 @@ pub fn bundled_state() -> State {
         current_theme: String::new(),
         display_density: 1.0,
         scaled_density: 1.0,
+        viewport_width: None,
+        viewport_height: None,
+        reduced_motion: false,
+        layout_direction: Default::default(),
         used_classes: IndexSet::new(),
         used_tags: IndexSet::new(),
         used_tag_classes: IndexSet::new(),
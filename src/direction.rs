@@ -0,0 +1,82 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Host layout direction for native output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutDirection {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+impl LayoutDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LayoutDirection::Ltr => "ltr",
+            LayoutDirection::Rtl => "rtl",
+        }
+    }
+}
+
+/// Direction required by an `rtl:` / `ltr:` prefixed class
+pub fn direction_variant(class: &str) -> Option<LayoutDirection> {
+    let prefixes = class.rsplit_once(':')?.0;
+    prefixes.split(':').find_map(|p| match p {
+        "rtl" => Some(LayoutDirection::Rtl),
+        "ltr" => Some(LayoutDirection::Ltr),
+        _ => None,
+    })
+}
+
+/// Web selector suffix matching elements inside a `dir` attribute, as Tailwind does
+pub fn selector_suffix(direction: LayoutDirection) -> String {
+    let dir = direction.as_str();
+    format!(":where([dir=\"{}\"], [dir=\"{}\"] *)", dir, dir)
+}
+
+/// Logical CSS properties (camelCase) and the Android start/end attributes they become.
+/// The flat attributes are left for the view to resolve against its own layout direction;
+/// composite output (`androidCornerRadii`, `androidBackground`) has no start/end slots, so it
+/// places them on physical corners and sides using `State.layout_direction`.
+pub const LOGICAL_TO_ANDROID: &[(&str, &str)] = &[
+    ("paddingInlineStart", "paddingStart"),
+    ("paddingInlineEnd", "paddingEnd"),
+    ("paddingInline", "paddingHorizontal"),
+    ("paddingBlock", "paddingVertical"),
+    ("marginInlineStart", "marginStart"),
+    ("marginInlineEnd", "marginEnd"),
+    ("marginInline", "marginHorizontal"),
+    ("marginBlock", "marginVertical"),
+    ("insetInlineStart", "start"),
+    ("insetInlineEnd", "end"),
+    ("borderInlineStartWidth", "borderStartWidth"),
+    ("borderInlineEndWidth", "borderEndWidth"),
+    ("borderInlineStartColor", "borderStartColor"),
+    ("borderInlineEndColor", "borderEndColor"),
+    ("borderStartStartRadius", "borderTopStartRadius"),
+    ("borderStartEndRadius", "borderTopEndRadius"),
+    ("borderEndStartRadius", "borderBottomStartRadius"),
+    ("borderEndEndRadius", "borderBottomEndRadius"),
+];
+
+/// Android start/end radius attribute that lands on each physical corner, clockwise from
+/// top-left, for the given layout direction
+pub fn logical_corners(rtl: bool) -> [&'static str; 4] {
+    let css = if rtl {
+        ["borderStartEndRadius", "borderStartStartRadius", "borderEndStartRadius", "borderEndEndRadius"]
+    } else {
+        ["borderStartStartRadius", "borderStartEndRadius", "borderEndEndRadius", "borderEndStartRadius"]
+    };
+    css.map(|corner| LOGICAL_TO_ANDROID.iter().find(|(logical, _)| *logical == corner).map_or(corner, |(_, android)| *android))
+}
+
+/// Rename logical properties to their Android start/end attributes, keeping their position
+pub fn apply_android_logical(styles: &mut IndexMap<String, serde_json::Value>) {
+    for (logical, android) in LOGICAL_TO_ANDROID {
+        if let Some(index) = styles.get_index_of(*logical) {
+            let value = styles.shift_remove_index(index).map(|(_, v)| v).unwrap_or_default();
+            styles.shift_insert(index.min(styles.len()), android.to_string(), value);
+        }
+    }
+}
@@ -8,6 +8,11 @@ pub extern "C" fn themed_styler_version() -> *const c_char {
     VERSION.as_ptr() as *const c_char
 }
 
+/// Render CSS for the given themes JSON.
+///
+/// # Safety
+/// `themes_json` must be null or a valid NUL-terminated C string. The returned
+/// string must be released with `themed_styler_free_string`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn themed_styler_render_css(
     themes_json: *const c_char,
@@ -31,6 +36,10 @@ pub unsafe extern "C" fn themed_styler_render_css(
     }
 }
 
+/// Free a string returned by this library.
+///
+/// # Safety
+/// `s` must be null or a pointer previously returned by this library and not yet freed.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn themed_styler_free_string(s: *mut c_char) {
     if !s.is_null() {
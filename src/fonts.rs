@@ -0,0 +1,205 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use crate::utils::split_top_level;
+use crate::CssProps;
+
+/// A registered font family, keyed in the theme's `fonts` map by the name used in `font-<key>`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct FontFamily {
+    /// CSS family name; defaults to the registry key
+    #[serde(default)]
+    pub family: Option<String>,
+    /// Families tried after this one, e.g. `["system-ui", "sans-serif"]`
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
+    #[serde(default)]
+    pub faces: Vec<FontFace>,
+    /// Android font family resource (`@font/inter`) used when no face has its own reference
+    #[serde(default)]
+    pub android: Option<String>,
+}
+
+/// One weight/style of a family
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FontFace {
+    #[serde(default = "default_weight")]
+    pub weight: u16,
+    #[serde(default = "default_style")]
+    pub style: String,
+    /// Web sources for `@font-face`, most preferred first
+    #[serde(default)]
+    pub src: Vec<String>,
+    /// Android font resource (`@font/inter_bold`) or asset path (`fonts/Inter-Bold.ttf`)
+    #[serde(default)]
+    pub android: Option<String>,
+}
+
+fn default_weight() -> u16 {
+    400
+}
+
+fn default_style() -> String {
+    "normal".to_string()
+}
+
+impl FontFamily {
+    pub fn family_name<'a>(&'a self, key: &'a str) -> &'a str {
+        self.family.as_deref().unwrap_or(key)
+    }
+
+    /// CSS `font-family` stack: the family followed by its fallbacks
+    pub fn stack(&self, key: &str) -> String {
+        std::iter::once(quote_family(self.family_name(key)))
+            .chain(self.fallbacks.iter().map(|f| quote_family(f)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Closest face by CSS matching rules, simplified: same style preferred, then nearest weight.
+    pub fn best_face(&self, weight: u16, style: &str) -> Option<&FontFace> {
+        self.faces
+            .iter()
+            .min_by_key(|f| ((f.style != style) as u32, (f.weight as i32 - weight as i32).unsigned_abs()))
+    }
+}
+
+fn quote_family(name: &str) -> String {
+    let generic = ["serif", "sans-serif", "monospace", "cursive", "fantasy", "system-ui", "ui-monospace", "ui-sans-serif", "ui-serif"];
+    if generic.contains(&name) || name.starts_with('"') || name.starts_with('-') {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name)
+    }
+}
+
+/// `font-<key>` for a registered family
+pub fn font_utility(name: &str, fonts: &IndexMap<String, FontFamily>) -> Option<CssProps> {
+    let family = fonts.get(name)?;
+    let mut props = CssProps::new();
+    props.insert("font-family".into(), json!(family.stack(name)).into());
+    Some(props)
+}
+
+/// Registered family named first in a `font-family` value (by CSS name or registry key)
+pub fn lookup<'a>(value: &str, fonts: &'a IndexMap<String, FontFamily>) -> Option<(&'a str, &'a FontFamily)> {
+    let first = split_top_level(value, ',').into_iter().next()?;
+    let name = first.trim().trim_matches(|c| c == '"' || c == '\'');
+    fonts
+        .iter()
+        .find(|(key, family)| family.family_name(key) == name || key.as_str() == name)
+        .map(|(key, family)| (key.as_str(), family))
+}
+
+/// `@font-face` rules for one family
+pub fn font_face_rules(key: &str, family: &FontFamily) -> Vec<(String, CssProps)> {
+    family
+        .faces
+        .iter()
+        .filter(|face| !face.src.is_empty())
+        .map(|face| {
+            let src: Vec<String> = face
+                .src
+                .iter()
+                .map(|url| match font_format(url) {
+                    Some(format) => format!("url({}) format(\"{}\")", quote_url(url), format),
+                    None => format!("url({})", quote_url(url)),
+                })
+                .collect();
+            let mut props = CssProps::new();
+            props.insert("font-family".into(), json!(quote_family(family.family_name(key))).into());
+            props.insert("src".into(), json!(src.join(", ")).into());
+            props.insert("font-weight".into(), json!(face.weight.to_string()).into());
+            props.insert("font-style".into(), json!(face.style).into());
+            props.insert("font-display".into(), json!("swap").into());
+            ("@font-face".to_string(), props)
+        })
+        .collect()
+}
+
+/// Quoted `url()` argument; quotes, backslashes and newlines are escaped so a source path
+/// can't end the string early
+fn quote_url(url: &str) -> String {
+    let mut out = String::with_capacity(url.len() + 2);
+    out.push('"');
+    for ch in url.chars() {
+        match ch {
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '\n' => out.push_str("\\a "),
+            _ => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn font_format(url: &str) -> Option<&'static str> {
+    let path = url.split(['?', '#']).next()?.to_ascii_lowercase();
+    Some(match path.rsplit('.').next()? {
+        "woff2" => "woff2",
+        "woff" => "woff",
+        "ttf" => "truetype",
+        "otf" => "opentype",
+        _ => return None,
+    })
+}
+
+/// CSS `font-weight` as a number (`bold` = 700)
+pub fn parse_weight(value: &serde_json::Value) -> u16 {
+    match value {
+        serde_json::Value::Number(n) => n.as_u64().unwrap_or(400) as u16,
+        serde_json::Value::String(s) => match s.as_str() {
+            "bold" | "bolder" => 700,
+            "normal" | "lighter" => 400,
+            other => other.parse().unwrap_or(400),
+        },
+        _ => 400,
+    }
+}
+
+/// Concrete Android typeface for a `font-family` stack at a weight and style.
+/// Registered families resolve to a font resource or asset; generic CSS families map to the
+/// Android system families (`sans-serif`, `serif`, `monospace`).
+pub fn android_typeface(value: &str, weight: u16, style: &str, fonts: &IndexMap<String, FontFamily>) -> Option<serde_json::Value> {
+    if let Some((key, family)) = lookup(value, fonts) {
+        let face = family.best_face(weight, style);
+        let source = face.and_then(|f| f.android.clone()).or_else(|| family.android.clone());
+        if let Some(source) = source {
+            let (kind, source) = match source.strip_prefix("@font/") {
+                Some(name) => ("resource", name.to_string()),
+                None => ("asset", source),
+            };
+            let mut fallbacks: Vec<&str> = Vec::new();
+            for system in family.fallbacks.iter().filter_map(|f| system_family(f)) {
+                if !fallbacks.contains(&system) {
+                    fallbacks.push(system);
+                }
+            }
+            return Some(json!({
+                "family": family.family_name(key),
+                "kind": kind,
+                "source": source,
+                "weight": weight,
+                "style": style,
+                // The face's own weight/style differ, so Android has to synthesize them
+                "synthetic": face.is_none_or(|f| f.weight != weight || f.style != style),
+                "fallbacks": fallbacks,
+            }));
+        }
+    }
+    // Web-only families fall through to the first generic family in the stack
+    let system = split_top_level(value, ',').iter().find_map(|f| system_family(f.trim().trim_matches('"')))?;
+    Some(json!({ "family": system, "kind": "system", "weight": weight, "style": style }))
+}
+
+fn system_family(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "sans-serif" | "system-ui" | "ui-sans-serif" | "-apple-system" | "Roboto" => "sans-serif",
+        "serif" | "ui-serif" | "Georgia" => "serif",
+        "monospace" | "ui-monospace" => "monospace",
+        _ => return None,
+    })
+}
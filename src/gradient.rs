@@ -0,0 +1,285 @@
+use indexmap::IndexMap;
+use serde_json::json;
+use crate::color::{evaluate_color, Color};
+use crate::utils::split_top_level;
+use crate::{get_tailwind_color_with_vars, CssProps};
+
+/// Color-stop list shared by every `from-*`/`via-*`/`to-*` rule.
+///
+/// Each rule only sets its own variables and repeats this same composition, so the result does not
+/// depend on rule order. Without `to-*` the end color falls back to the via (or from) color at zero
+/// alpha, as in Tailwind.
+const GRADIENT_STOPS: &str = "var(--tw-gradient-from, transparent) var(--tw-gradient-from-position, ), \
+var(--tw-gradient-via-stop, ) \
+var(--tw-gradient-to, var(--tw-gradient-via-fallback, var(--tw-gradient-from-fallback, transparent))) var(--tw-gradient-to-position, )";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopRole {
+    From,
+    Via,
+    To,
+}
+
+/// A parsed `bg-gradient-to-*` / `from-*` / `via-*` / `to-*` utility.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradientUtility {
+    /// CSS direction keyword, e.g. `to right`
+    Direction(&'static str),
+    Color(StopRole, String),
+    /// Stop position in percent (`from-10%`)
+    Position(StopRole, f32),
+}
+
+pub fn parse_gradient_utility(class: &str, vars: &IndexMap<String, String>) -> Option<GradientUtility> {
+    if let Some(dir) = class.strip_prefix("bg-gradient-to-") {
+        let direction = match dir {
+            "t" => "to top",
+            "tr" => "to top right",
+            "r" => "to right",
+            "br" => "to bottom right",
+            "b" => "to bottom",
+            "bl" => "to bottom left",
+            "l" => "to left",
+            "tl" => "to top left",
+            _ => return None,
+        };
+        return Some(GradientUtility::Direction(direction));
+    }
+    let (role, rest) = [("from-", StopRole::From), ("via-", StopRole::Via), ("to-", StopRole::To)]
+        .into_iter()
+        .find_map(|(prefix, role)| class.strip_prefix(prefix).map(|rest| (role, rest)))?;
+    if let Some(pct) = rest.strip_suffix('%') {
+        return pct.parse::<f32>().ok().map(|p| GradientUtility::Position(role, p));
+    }
+    let color = match rest {
+        "transparent" | "white" | "black" => rest.to_string(),
+        "current" => "currentColor".to_string(),
+        _ => match rest.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            Some(arbitrary) => arbitrary.replace('_', " "),
+            None => get_tailwind_color_with_vars(rest, vars)?,
+        },
+    };
+    Some(GradientUtility::Color(role, color))
+}
+
+impl GradientUtility {
+    pub fn web_props(&self) -> CssProps {
+        let mut p = CssProps::new();
+        match self {
+            GradientUtility::Direction(dir) => {
+                p.insert("background-image".into(), json!(format!("linear-gradient({}, var(--tw-gradient-stops))", dir)).into());
+            }
+            GradientUtility::Color(role, color) => {
+                match role {
+                    StopRole::From => {
+                        p.insert("--tw-gradient-from".into(), json!(color).into());
+                        p.insert("--tw-gradient-from-fallback".into(), json!(transparent_of(color)).into());
+                    }
+                    StopRole::Via => {
+                        p.insert("--tw-gradient-via-stop".into(), json!(format!("{} var(--tw-gradient-via-position, ),", color)).into());
+                        p.insert("--tw-gradient-via-fallback".into(), json!(transparent_of(color)).into());
+                    }
+                    StopRole::To => {
+                        p.insert("--tw-gradient-to".into(), json!(color).into());
+                    }
+                }
+                p.insert("--tw-gradient-stops".into(), json!(GRADIENT_STOPS).into());
+            }
+            GradientUtility::Position(role, pct) => {
+                p.insert(format!("--tw-gradient-{}-position", role_name(*role)), json!(format!("{}%", pct)).into());
+            }
+        }
+        p
+    }
+}
+
+fn role_name(role: StopRole) -> &'static str {
+    match role {
+        StopRole::From => "from",
+        StopRole::Via => "via",
+        StopRole::To => "to",
+    }
+}
+
+/// The same color at zero alpha, so fading to "nothing" keeps the hue
+fn transparent_of(color: &str) -> String {
+    match Color::parse(color) {
+        Some(c) => Color { a: 0.0, ..c }.to_hex(),
+        None => "transparent".to_string(),
+    }
+}
+
+/// A resolved linear gradient: angle in CSS degrees (0 = to top, 90 = to right) and color stops
+/// with optional positions in 0..=1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearGradient {
+    pub angle: f32,
+    pub stops: Vec<(String, Option<f32>)>,
+}
+
+impl LinearGradient {
+    /// Compose the Tailwind utilities on one element. Requires a `bg-gradient-to-*` direction.
+    pub fn from_utilities(utilities: &[GradientUtility]) -> Option<LinearGradient> {
+        let mut direction = None;
+        let mut colors: [Option<String>; 3] = [None, None, None];
+        let mut positions: [Option<f32>; 3] = [None, None, None];
+        for u in utilities {
+            match u {
+                GradientUtility::Direction(d) => direction = Some(*d),
+                GradientUtility::Color(role, c) => colors[*role as usize] = Some(c.clone()),
+                GradientUtility::Position(role, p) => positions[*role as usize] = Some(p / 100.0),
+            }
+        }
+        let angle = direction_angle(direction?)?;
+        let [from, via, to] = colors;
+        let to = to.unwrap_or_else(|| transparent_of(via.as_deref().or(from.as_deref()).unwrap_or("transparent")));
+        let mut stops = vec![(from.unwrap_or_else(|| "transparent".to_string()), positions[0])];
+        if let Some(via) = via {
+            stops.push((via, positions[1]));
+        }
+        stops.push((to, positions[2]));
+        Some(LinearGradient { angle, stops })
+    }
+
+    /// Parse `linear-gradient([<angle> | to <side-or-corner>,] <color> [<pct>]{0,2}, ...)`.
+    /// Stop positions other than percentages are ignored.
+    pub fn parse(value: &str) -> Option<LinearGradient> {
+        let inner = value.trim().strip_prefix("linear-gradient(")?.strip_suffix(')')?;
+        let mut args = split_top_level(inner, ',').into_iter().peekable();
+        let first = args.peek()?.trim().to_string();
+        let angle = if first.starts_with("to ") {
+            args.next();
+            direction_angle(&first)?
+        } else if let Some(angle) = parse_angle(&first) {
+            args.next();
+            angle
+        } else {
+            180.0
+        };
+        let mut stops = Vec::new();
+        for arg in args {
+            let mut color = None;
+            let mut positions = Vec::new();
+            for token in split_top_level(&arg, ' ') {
+                match token.strip_suffix('%').and_then(|p| p.parse::<f32>().ok()) {
+                    Some(p) => positions.push(p / 100.0),
+                    None if color.is_none() => color = Some(token),
+                    None => {}
+                }
+            }
+            let color = color?;
+            if positions.is_empty() {
+                stops.push((color, None));
+                continue;
+            }
+            for p in positions {
+                stops.push((color.clone(), Some(p)));
+            }
+        }
+        (stops.len() >= 2).then_some(LinearGradient { angle, stops })
+    }
+
+    /// Stop positions with CSS fix-up: first defaults to 0, last to 1, positions never decrease,
+    /// and runs of missing positions are spread evenly between their neighbours.
+    pub fn resolved_positions(&self) -> Vec<f32> {
+        let n = self.stops.len();
+        let mut pos: Vec<Option<f32>> = self.stops.iter().map(|(_, p)| *p).collect();
+        if pos[0].is_none() {
+            pos[0] = Some(0.0);
+        }
+        if pos[n - 1].is_none() {
+            pos[n - 1] = Some(1.0);
+        }
+        let mut max = 0.0f32;
+        for p in pos.iter_mut().flatten() {
+            max = max.max(*p);
+            *p = max;
+        }
+        let mut i = 0;
+        while i < n {
+            if pos[i].is_some() {
+                i += 1;
+                continue;
+            }
+            let start = i - 1;
+            let end = (i..n).find(|&j| pos[j].is_some()).unwrap_or(n - 1);
+            let (a, b) = (pos[start].unwrap_or(0.0), pos[end].unwrap_or(1.0));
+            for (k, slot) in pos.iter_mut().enumerate().take(end).skip(i) {
+                *slot = Some(a + (b - a) * (k - start) as f32 / (end - start) as f32);
+            }
+            i = end;
+        }
+        pos.into_iter().map(|p| p.unwrap_or(0.0)).collect()
+    }
+
+    /// Structured Android description for a `GradientDrawable`. Colors are resolved; the
+    /// orientation is the closest `GradientDrawable.Orientation` to the CSS angle.
+    pub fn to_android(&self, current_color: Option<&str>, vars: &IndexMap<String, String>) -> serde_json::Value {
+        let colors: Vec<String> = self
+            .stops
+            .iter()
+            .map(|(c, _)| {
+                evaluate_color(c, current_color, vars).map(Color::to_hex).unwrap_or_else(|| c.clone())
+            })
+            .collect();
+        json!({
+            "type": "linear",
+            "angle": self.angle,
+            "orientation": android_orientation(self.angle),
+            "colors": colors,
+            // Rounded so f32 noise doesn't leak into the JSON (0.4 rather than 0.4000000059604645)
+            "positions": self.resolved_positions().iter().map(|p| (*p as f64 * 1e4).round() / 1e4).collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn direction_angle(direction: &str) -> Option<f32> {
+    let mut sides: Vec<&str> = direction.strip_prefix("to ")?.split_whitespace().collect();
+    sides.sort_unstable();
+    Some(match sides.as_slice() {
+        ["top"] => 0.0,
+        ["right", "top"] => 45.0,
+        ["right"] => 90.0,
+        ["bottom", "right"] => 135.0,
+        ["bottom"] => 180.0,
+        ["bottom", "left"] => 225.0,
+        ["left"] => 270.0,
+        ["left", "top"] => 315.0,
+        _ => return None,
+    })
+}
+
+fn parse_angle(s: &str) -> Option<f32> {
+    if s == "0" {
+        return Some(0.0);
+    }
+    for (suffix, factor) in [("deg", 1.0), ("grad", 0.9), ("rad", 180.0 / std::f32::consts::PI), ("turn", 360.0)] {
+        if let Some(n) = s.strip_suffix(suffix) {
+            return n.parse::<f32>().ok().map(|v| v * factor);
+        }
+    }
+    None
+}
+
+fn android_orientation(angle: f32) -> &'static str {
+    const ORIENTATIONS: [&str; 8] = ["BOTTOM_TOP", "BL_TR", "LEFT_RIGHT", "TL_BR", "TOP_BOTTOM", "TR_BL", "RIGHT_LEFT", "BR_TL"];
+    let index = (angle.rem_euclid(360.0) / 45.0).round() as usize % 8;
+    ORIENTATIONS[index]
+}
+
+/// Android output for the element's gradient: Tailwind utilities win over a theme
+/// `background-image`. Emits `androidGradient` and leaves the CSS value untouched.
+pub fn apply_android_gradient(
+    out: &mut IndexMap<String, serde_json::Value>,
+    utilities: &[GradientUtility],
+    vars: &IndexMap<String, String>,
+) {
+    let gradient = LinearGradient::from_utilities(utilities).or_else(|| {
+        let image = out.get("backgroundImage").or_else(|| out.get("background"))?.as_str()?;
+        LinearGradient::parse(image)
+    });
+    if let Some(gradient) = gradient {
+        let current_color = out.get("color").and_then(|v| v.as_str()).map(|s| s.to_string());
+        out.insert("androidGradient".into(), gradient.to_android(current_color.as_deref(), vars));
+    }
+}
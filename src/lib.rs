@@ -6,13 +6,36 @@ use serde_json::json;
 use wasm_bindgen::prelude::*;
 mod default_state;
 mod color;
+mod children;
+mod shadow;
+mod border;
+mod gradient;
+mod shorthands;
+pub mod units;
+pub mod contrast;
+mod dark;
+mod animation;
+pub mod direction;
+pub mod property;
+mod value;
+pub use value::PropValue;
+mod lowering;
+mod background;
+pub mod fonts;
+pub mod accessibility;
 use default_state::bundled_state;
+use utils::{
+    camel_case, class_to_selector, css_escape_class, merge_props, parse_prefixed_class, resolve_vars,
+    should_emit_selector, split_tag_class_key,
+};
+#[cfg(test)]
+use utils::parse_var_references;
 
 // Default display density (1.0 = mdpi baseline)
 fn default_display_density() -> f32 { 1.0 }
 fn default_scaled_density() -> f32 { 1.0 }
 
-pub type CssProps = IndexMap<String, serde_json::Value>;
+pub type CssProps = IndexMap<String, PropValue>;
 pub type SelectorStyles = IndexMap<String, CssProps>; // selector -> props
 
 /// Convert dp to pixels using display density
@@ -20,41 +43,6 @@ fn dp_to_px(dp: f32, density: f32) -> i32 {
     (dp * density).round() as i32
 }
 
-/// Convert sp to pixels using scaled density  
-fn sp_to_px(sp: f32, scaled_density: f32) -> f32 {
-    sp * scaled_density
-}
-
-/// Parse a CSS value and convert to Android pixels if needed
-fn parse_and_convert_to_px(value: &serde_json::Value, density: f32) -> Option<serde_json::Value> {
-    match value {
-        serde_json::Value::Number(n) => {
-            // Bare number treated as dp
-            let dp = n.as_f64()? as f32;
-            Some(serde_json::json!(dp_to_px(dp, density)))
-        }
-        serde_json::Value::String(s) => {
-            // Parse string with units
-            let trimmed = s.trim();
-            if trimmed.ends_with("px") {
-                // Treat px as density-independent pixels (dp) for cross-platform parity
-                let px = trimmed.trim_end_matches("px").trim().parse::<f32>().ok()?;
-                Some(serde_json::json!(dp_to_px(px, density)))
-            } else if trimmed.ends_with("dp") {
-                let dp = trimmed.trim_end_matches("dp").trim().parse::<f32>().ok()?;
-                Some(serde_json::json!(dp_to_px(dp, density)))
-            } else if let Ok(num) = trimmed.parse::<f32>() {
-                // Bare number as string, treat as dp
-                Some(serde_json::json!(dp_to_px(num, density)))
-            } else {
-                // Keep as-is (e.g., "wrap_content", "match_parent")
-                None
-            }
-        }
-        _ => None
-    }
-}
-
 fn deserialize_variables<'de, D>(deserializer: D) -> Result<IndexMap<String, String>, D::Error>
 where
     D: Deserializer<'de>,
@@ -108,7 +96,9 @@ fn flatten_variables(prefix: Option<&str>, value: &serde_json::Value, out: &mut
     }
 }
 
+/// Build with `ThemeEntry::default()` or from JSON; new optional sections are added over time.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[non_exhaustive]
 pub struct ThemeEntry {
     #[serde(default)]
     pub name: Option<String>,
@@ -120,6 +110,31 @@ pub struct ThemeEntry {
     pub variables: IndexMap<String, String>,
     #[serde(default, deserialize_with = "deserialize_variables")]
     pub breakpoints: IndexMap<String, String>,
+    /// Foreground variable -> background variable pairs checked by `contrast_audit`
+    #[serde(default)]
+    pub contrast_pairs: IndexMap<String, String>,
+    /// rem base and the meaning of `px` on native platforms
+    #[serde(default)]
+    pub units: units::UnitSettings,
+    /// Named `@keyframes` (keyframe selector -> properties); overrides the built-in spin/ping/pulse/bounce
+    #[serde(default)]
+    pub keyframes: IndexMap<String, animation::Keyframes>,
+    /// Font registry for `font-<key>`: web sources for `@font-face` and Android typefaces
+    #[serde(default)]
+    pub fonts: IndexMap<String, fonts::FontFamily>,
+    /// Opt-in native accessibility policies (touch target size, font scale bounds)
+    #[serde(default)]
+    pub accessibility: accessibility::AccessibilityPolicy,
+}
+
+impl ThemeEntry {
+    /// Generate a dark counterpart that `inherits` this theme (registered as `key`).
+    /// Color variables get their perceptual (OKLCH) lightness inverted with hue and chroma kept,
+    /// and `contrast_pairs` foregrounds are adjusted to at least WCAG AA against their background.
+    /// Override individual values on the returned entry as needed.
+    pub fn derive_dark(&self, key: &str) -> ThemeEntry {
+        dark::derive_dark_theme(key, self, &self.variables, &self.contrast_pairs, contrast::AA_NORMAL)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -133,6 +148,17 @@ pub struct State {
     pub display_density: f32, // Android displayMetrics.density (1.0 for mdpi, 2.0 for xhdpi, etc.)
     #[serde(default = "default_scaled_density")]
     pub scaled_density: f32,  // Android displayMetrics.scaledDensity for SP conversions
+    /// Screen size in dp supplied by the host, for vw/vh units
+    #[serde(default)]
+    pub viewport_width: Option<f32>,
+    #[serde(default)]
+    pub viewport_height: Option<f32>,
+    /// Host "reduce motion" setting; selects `motion-reduce:` over `motion-safe:` classes natively
+    #[serde(default)]
+    pub reduced_motion: bool,
+    /// Host layout direction; selects `rtl:` over `ltr:` classes natively
+    #[serde(default)]
+    pub layout_direction: direction::LayoutDirection,
     
     #[serde(default)]
     pub used_classes: IndexSet<String>,   // observed classes on elements
@@ -152,7 +178,7 @@ pub enum Error {
 impl State {
     pub fn new_default() -> Self {
         // Prefer embedded Rust bundled defaults
-        return bundled_state();
+        bundled_state()
     }
 
     /// Public helper to access the embedded default state.
@@ -191,9 +217,52 @@ impl State {
         entry.breakpoints = map;
     }
 
-    pub fn process_styles(&self, mut styles: IndexMap<String, serde_json::Value>) -> IndexMap<String, serde_json::Value> {
-        let density = self.display_density;
-        
+    /// Screen size in dp, used to resolve `vw`/`vh` on native platforms.
+    pub fn set_viewport(&mut self, width: f32, height: f32) {
+        self.viewport_width = Some(width);
+        self.viewport_height = Some(height);
+    }
+
+    /// Host accessibility setting for `motion-reduce:` / `motion-safe:` classes on native platforms.
+    pub fn set_reduced_motion(&mut self, reduced: bool) {
+        self.reduced_motion = reduced;
+    }
+
+    /// Host layout direction for `rtl:` / `ltr:` classes and physical alignment on native platforms.
+    pub fn set_layout_direction(&mut self, direction: direction::LayoutDirection) {
+        self.layout_direction = direction;
+    }
+
+    /// Unit context for native conversions: host metrics plus the effective theme's unit settings.
+    fn unit_context(&self) -> units::UnitContext {
+        let mut settings = units::UnitSettings::default();
+        for name in self.theme_chain() {
+            if let Some(entry) = self.themes.get(&name) {
+                settings.rem_base = settings.rem_base.or(entry.units.rem_base);
+                settings.px = settings.px.or(entry.units.px);
+            }
+        }
+        let scaled_density = self.accessibility_policy().clamp_scaled_density(self.display_density, self.scaled_density);
+        let mut ctx = units::UnitContext::new(self.display_density, scaled_density, &settings);
+        ctx.viewport_width = self.viewport_width;
+        ctx.viewport_height = self.viewport_height;
+        ctx
+    }
+
+    pub fn process_styles(&self, styles: IndexMap<String, serde_json::Value>) -> IndexMap<String, serde_json::Value> {
+        let units = self.unit_context();
+
+        // Collapse kebab and camel spellings through the registry so `padding-top` and
+        // `paddingTop` can't both survive; web-only properties are dropped
+        let mut canonical = IndexMap::new();
+        for (k, v) in styles {
+            if property::CssProperty::parse(&k).supported_on(property::Platform::Native) {
+                property::merge_property(&mut canonical, &k, v);
+            }
+        }
+        let mut styles: IndexMap<String, serde_json::Value> =
+            canonical.into_iter().map(|(k, v)| (property::CssProperty::parse(&k).camel(), v)).collect();
+
         // Expand shorthands
         // Order matters: Horizontal/Vertical should be expanded before general shorthands
         // so that specific ones win if they were already present.
@@ -205,12 +274,6 @@ impl State {
             styles.entry("paddingTop".into()).or_insert(pv.clone());
             styles.entry("paddingBottom".into()).or_insert(pv.clone());
         }
-        if let Some(p) = styles.get("padding").cloned() {
-            styles.entry("paddingTop".into()).or_insert(p.clone());
-            styles.entry("paddingBottom".into()).or_insert(p.clone());
-            styles.entry("paddingLeft".into()).or_insert(p.clone());
-            styles.entry("paddingRight".into()).or_insert(p.clone());
-        }
         if let Some(mh) = styles.get("marginHorizontal").cloned() {
             styles.entry("marginLeft".into()).or_insert(mh.clone());
             styles.entry("marginRight".into()).or_insert(mh.clone());
@@ -219,17 +282,28 @@ impl State {
             styles.entry("marginTop".into()).or_insert(mv.clone());
             styles.entry("marginBottom".into()).or_insert(mv.clone());
         }
-        if let Some(m) = styles.get("margin").cloned() {
-            styles.entry("marginTop".into()).or_insert(m.clone());
-            styles.entry("marginBottom".into()).or_insert(m.clone());
-            styles.entry("marginLeft".into()).or_insert(m.clone());
-            styles.entry("marginRight".into()).or_insert(m.clone());
-        }
-        if let Some(r) = styles.get("borderRadius").cloned() {
-            styles.entry("borderTopLeftRadius".into()).or_insert(r.clone());
-            styles.entry("borderTopRightRadius".into()).or_insert(r.clone());
-            styles.entry("borderBottomLeftRadius".into()).or_insert(r.clone());
-            styles.entry("borderBottomRightRadius".into()).or_insert(r.clone());
+        // 1-4 value box shorthands; a multi-value aggregate can't be converted, so only the sides remain
+        for shorthand in ["padding", "margin", "inset", "borderWidth", "borderColor", "borderStyle", "borderRadius"] {
+            let Some(value) = styles.get(shorthand).cloned() else { continue };
+            let longhands: Vec<(String, serde_json::Value)> = match &value {
+                serde_json::Value::String(s) => shorthands::expand_box_shorthand(&utils::kebab_case(shorthand), s)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(k, v)| (camel_case(&k), json!(v)))
+                    .collect(),
+                serde_json::Value::Number(_) => shorthands::expand_box_shorthand(&utils::kebab_case(shorthand), "0")
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(k, _)| (camel_case(&k), value.clone()))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            for (k, v) in longhands {
+                styles.entry(k).or_insert(v);
+            }
+            if value.as_str().is_some_and(shorthands::is_multi_value) {
+                styles.shift_remove(shorthand);
+            }
         }
 
         // Convert only dimension properties to pixels
@@ -241,12 +315,16 @@ impl State {
             "marginHorizontal", "marginVertical",
             "borderRadius", "borderTopLeftRadius", "borderTopRightRadius", "borderBottomLeftRadius", "borderBottomRightRadius",
             "borderWidth", "borderTopWidth", "borderBottomWidth", "borderLeftWidth", "borderRightWidth",
+            "top", "right", "bottom", "left",
+            "paddingStart", "paddingEnd", "marginStart", "marginEnd", "start", "end",
+            "borderStartWidth", "borderEndWidth",
+            "borderTopStartRadius", "borderTopEndRadius", "borderBottomStartRadius", "borderBottomEndRadius",
             "gap", "rowGap", "columnGap", "elevation", "fontSize", "lineHeight", "letterSpacing"
         ];
         
         for prop in &dimension_props {
             if let Some(value) = styles.get(*prop).cloned() {
-                if let Some(converted) = parse_and_convert_to_px(&value, density) {
+                if let Some(converted) = units.to_px(&value) {
                     styles.insert(prop.to_string(), converted);
                 }
             }
@@ -294,6 +372,10 @@ impl State {
             "current_theme": self.current_theme,
             "display_density": self.display_density,
             "scaled_density": self.scaled_density,
+            "viewport_width": self.viewport_width,
+            "viewport_height": self.viewport_height,
+            "reduced_motion": self.reduced_motion,
+            "layout_direction": self.layout_direction,
             "used_classes": self.used_classes,
             "used_tags": self.used_tags,
             "used_tag_classes": self.used_tag_classes,
@@ -309,6 +391,8 @@ impl State {
         // Compute CSS resolved from the effective theme (with inheritance)
         let (eff, vars) = self.effective_theme_all();
         let bps = self.effective_breakpoints();
+        let keyframes = self.effective_keyframes();
+        let font_registry = self.effective_fonts();
         let mut rules: Vec<(String, CssProps)> = Vec::new();
         
         // Build closure: if a (tag,class) pair is observed, consider both the tag and the class as used too
@@ -335,33 +419,102 @@ impl State {
         // Also emit dynamic utility properties for used classes
         for class in &used_classes {
             let (bp_key, hover, base) = parse_prefixed_class(class);
-            let selector = if hover { format!(".{}:hover", css_escape_class(&base)) } else { format!(".{}", css_escape_class(&base)) };
+            // Reduced-motion and direction variants keep the full class so they only reach elements that opted in
+            let motion = animation::motion_variant(class);
+            let dir = direction::direction_variant(class);
+            let class_name = if motion.is_some() || dir.is_some() { class.as_str() } else { base.as_str() };
+            let mut selector = if hover { format!(".{}:hover", css_escape_class(class_name)) } else { format!(".{}", css_escape_class(class_name)) };
+            if let Some(dir) = dir {
+                selector.push_str(&direction::selector_suffix(dir));
+            }
 
             // 1) Exact selector in effective theme (e.g. ".x:hover")
             if let Some(props) = eff.get(&selector) {
-                let final_sel = wrap_with_media(&selector, bp_key.as_deref(), &bps);
+                let final_sel = wrap_with_media(&selector, bp_key.as_deref(), motion, &bps);
                 rules.push((final_sel, props.clone()));
                 continue;
             }
-            // 2) Dynamic generation for the base class (ignoring hover/breakpoint for props)
+            // 2) Child utilities (space-*, divide-*) style the element's children
+            if let Some(child) = children::parse_child_utility(&base, &vars) {
+                let sel = format!("{}{}", selector, children::CHILD_SELECTOR_SUFFIX);
+                let final_sel = wrap_with_media(&sel, bp_key.as_deref(), motion, &bps);
+                rules.push((final_sel, child.web_props()));
+                continue;
+            }
+            // 3) Gradient utilities compose through --tw-gradient-* variables
+            if let Some(gradient) = gradient::parse_gradient_utility(&base, &vars) {
+                let final_sel = wrap_with_media(&selector, bp_key.as_deref(), motion, &bps);
+                rules.push((final_sel, gradient.web_props()));
+                continue;
+            }
+            // 4) Animations reference built-in or theme keyframes
+            if let Some(animation) = base.strip_prefix("animate-").and_then(|name| animation::animate_utility(name, &vars, &keyframes)) {
+                let final_sel = wrap_with_media(&selector, bp_key.as_deref(), motion, &bps);
+                let mut props = CssProps::new();
+                props.insert("animation".into(), json!(animation).into());
+                rules.push((final_sel, props));
+                continue;
+            }
+            // 5) Registered fonts take over their font-<key> utility
+            if let Some(props) = base.strip_prefix("font-").and_then(|name| fonts::font_utility(name, &font_registry)) {
+                let final_sel = wrap_with_media(&selector, bp_key.as_deref(), motion, &bps);
+                rules.push((final_sel, props));
+                continue;
+            }
+            // 6) Dynamic generation for the base class (ignoring hover/breakpoint for props)
             if let Some(dynamic_props) = dynamic_css_properties_for_class(&base, &vars) {
-                let sel = if hover { format!(".{}:hover", css_escape_class(&base)) } else { format!(".{}", css_escape_class(&base)) };
-                let final_sel = wrap_with_media(&sel, bp_key.as_deref(), &bps);
+                let final_sel = wrap_with_media(&selector, bp_key.as_deref(), motion, &bps);
                 rules.push((final_sel, dynamic_props));
                 continue;
             }
-            // 3) Fallback: class key itself in theme (rare)
+            // 7) Fallback: class key itself in theme (rare)
             if let Some(props) = eff.get(&base) {
-                let final_sel = wrap_with_media(&selector, bp_key.as_deref(), &bps);
+                let final_sel = wrap_with_media(&selector, bp_key.as_deref(), motion, &bps);
                 rules.push((final_sel, props.clone()));
             }
         }
 
+        // @keyframes for every animation and @font-face for every registered font referenced by
+        // the emitted rules
+        let mut animated = IndexSet::new();
+        let mut used_fonts = IndexSet::new();
+        for (_, props) in &rules {
+            for (key, value) in props {
+                match property::CssProperty::parse(key).id() {
+                    "animation" | "animation-name" => {
+                        if let Some(spec) = value.as_str().and_then(animation::parse_animation) {
+                            animated.insert(spec.name);
+                        }
+                    }
+                    "font-family" => {
+                        if let Some((font_key, _)) = value.as_str().and_then(|v| fonts::lookup(&resolve_vars(v, &vars), &font_registry)) {
+                            used_fonts.insert(font_key.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let font_faces: Vec<(String, CssProps)> = used_fonts
+            .iter()
+            .flat_map(|key| fonts::font_face_rules(key, &font_registry[key]))
+            .collect();
+        rules.splice(0..0, font_faces);
+        for name in animated {
+            if let Some(frames) = keyframes.get(&name) {
+                for (step, props) in frames {
+                    rules.push((format!("@keyframes {} {{{}}}", name, step), props.clone()));
+                }
+            }
+        }
+
         post_process_css(&rules, &vars)
     }
 
     pub fn android_base_styles(&self, selector: &str, classes: &[String]) -> IndexMap<String, serde_json::Value> {
         let (eff, vars) = self.effective_theme_all();
+        let keyframes = self.effective_keyframes();
+        let font_registry = self.effective_fonts();
         let mut out: IndexMap<String, serde_json::Value> = IndexMap::new();
 
         // Pre-insert androidOrientation to ensure it's early in the map for gap processing
@@ -372,89 +525,89 @@ impl State {
         // 1. Apply hardcoded platform defaults (lowest priority)
         match selector.to_lowercase().as_str() {
             "div" => {
-                combined_props.insert("width".into(), json!("match_parent"));
+                combined_props.insert("width".into(), json!("match_parent").into());
             }
             "p" => {
-                combined_props.insert("width".into(), json!("match_parent"));
-                combined_props.insert("margin-vertical".into(), json!("16px"));
+                combined_props.insert("width".into(), json!("match_parent").into());
+                combined_props.insert("margin-vertical".into(), json!("16px").into());
             }
             "h1" => {
-                combined_props.insert("width".into(), json!("match_parent"));
-                combined_props.insert("font-size".into(), json!("32px"));
-                combined_props.insert("font-weight".into(), json!("bold"));
-                combined_props.insert("margin-vertical".into(), json!("21.44px"));
+                combined_props.insert("width".into(), json!("match_parent").into());
+                combined_props.insert("font-size".into(), json!("32px").into());
+                combined_props.insert("font-weight".into(), json!("bold").into());
+                combined_props.insert("margin-vertical".into(), json!("21.44px").into());
             }
             "h2" => {
-                combined_props.insert("width".into(), json!("match_parent"));
-                combined_props.insert("font-size".into(), json!("24px"));
-                combined_props.insert("font-weight".into(), json!("bold"));
-                combined_props.insert("margin-vertical".into(), json!("19.92px"));
+                combined_props.insert("width".into(), json!("match_parent").into());
+                combined_props.insert("font-size".into(), json!("24px").into());
+                combined_props.insert("font-weight".into(), json!("bold").into());
+                combined_props.insert("margin-vertical".into(), json!("19.92px").into());
             }
             "h3" => {
-                combined_props.insert("width".into(), json!("match_parent"));
-                combined_props.insert("font-size".into(), json!("18.72px"));
-                combined_props.insert("font-weight".into(), json!("bold"));
-                combined_props.insert("margin-vertical".into(), json!("18.72px"));
+                combined_props.insert("width".into(), json!("match_parent").into());
+                combined_props.insert("font-size".into(), json!("18.72px").into());
+                combined_props.insert("font-weight".into(), json!("bold").into());
+                combined_props.insert("margin-vertical".into(), json!("18.72px").into());
             }
             "h4" => {
-                combined_props.insert("width".into(), json!("match_parent"));
-                combined_props.insert("font-size".into(), json!("16px"));
-                combined_props.insert("font-weight".into(), json!("bold"));
-                combined_props.insert("margin-vertical".into(), json!("21.28px"));
+                combined_props.insert("width".into(), json!("match_parent").into());
+                combined_props.insert("font-size".into(), json!("16px").into());
+                combined_props.insert("font-weight".into(), json!("bold").into());
+                combined_props.insert("margin-vertical".into(), json!("21.28px").into());
             }
             "h5" => {
-                combined_props.insert("width".into(), json!("match_parent"));
-                combined_props.insert("font-size".into(), json!("13.28px"));
-                combined_props.insert("font-weight".into(), json!("bold"));
-                combined_props.insert("margin-vertical".into(), json!("22.17px"));
+                combined_props.insert("width".into(), json!("match_parent").into());
+                combined_props.insert("font-size".into(), json!("13.28px").into());
+                combined_props.insert("font-weight".into(), json!("bold").into());
+                combined_props.insert("margin-vertical".into(), json!("22.17px").into());
             }
             "h6" => {
-                combined_props.insert("width".into(), json!("match_parent"));
-                combined_props.insert("font-size".into(), json!("10.72px"));
-                combined_props.insert("font-weight".into(), json!("bold"));
-                combined_props.insert("margin-vertical".into(), json!("24.96px"));
+                combined_props.insert("width".into(), json!("match_parent").into());
+                combined_props.insert("font-size".into(), json!("10.72px").into());
+                combined_props.insert("font-weight".into(), json!("bold").into());
+                combined_props.insert("margin-vertical".into(), json!("24.96px").into());
             }
             "input" => {
-                combined_props.insert("padding-vertical".into(), json!("8px"));
-                combined_props.insert("padding-horizontal".into(), json!("12px"));
-                combined_props.insert("border-radius".into(), json!("4px"));
-                combined_props.insert("border-width".into(), json!("1px"));
-                combined_props.insert("border-color".into(), json!("#cccccc"));
-                combined_props.insert("background-color".into(), json!("#ffffff"));
-                combined_props.insert("color".into(), json!("#000000"));
-                combined_props.insert("placeholder-color".into(), json!("#88888870"));
-                combined_props.insert("min-height".into(), json!("40px"));
-                combined_props.insert("android-gravity".into(), json!("center_vertical"));
+                combined_props.insert("padding-vertical".into(), json!("8px").into());
+                combined_props.insert("padding-horizontal".into(), json!("12px").into());
+                combined_props.insert("border-radius".into(), json!("4px").into());
+                combined_props.insert("border-width".into(), json!("1px").into());
+                combined_props.insert("border-color".into(), json!("#cccccc").into());
+                combined_props.insert("background-color".into(), json!("#ffffff").into());
+                combined_props.insert("color".into(), json!("#000000").into());
+                combined_props.insert("placeholder-color".into(), json!("#88888870").into());
+                combined_props.insert("min-height".into(), json!("40px").into());
+                combined_props.insert("android-gravity".into(), json!("center_vertical").into());
             }
             "select" => {
-                combined_props.insert("padding-vertical".into(), json!("8px"));
-                combined_props.insert("padding-horizontal".into(), json!("12px"));
-                combined_props.insert("border-radius".into(), json!("4px"));
-                combined_props.insert("border-width".into(), json!("1px"));
-                combined_props.insert("border-color".into(), json!("#cccccc"));
-                combined_props.insert("background-color".into(), json!("#ffffff"));
-                combined_props.insert("color".into(), json!("#000000"));
-                combined_props.insert("min-height".into(), json!("40px"));
-                combined_props.insert("android-gravity".into(), json!("center_vertical"));
+                combined_props.insert("padding-vertical".into(), json!("8px").into());
+                combined_props.insert("padding-horizontal".into(), json!("12px").into());
+                combined_props.insert("border-radius".into(), json!("4px").into());
+                combined_props.insert("border-width".into(), json!("1px").into());
+                combined_props.insert("border-color".into(), json!("#cccccc").into());
+                combined_props.insert("background-color".into(), json!("#ffffff").into());
+                combined_props.insert("color".into(), json!("#000000").into());
+                combined_props.insert("min-height".into(), json!("40px").into());
+                combined_props.insert("android-gravity".into(), json!("center_vertical").into());
             }
             "textarea" => {
-                combined_props.insert("padding".into(), json!("12px"));
-                combined_props.insert("border-radius".into(), json!("4px"));
-                combined_props.insert("border-width".into(), json!("1px"));
-                combined_props.insert("border-color".into(), json!("#cccccc"));
-                combined_props.insert("background-color".into(), json!("#ffffff"));
-                combined_props.insert("color".into(), json!("#000000"));
-                combined_props.insert("placeholder-color".into(), json!("color-mix(in srgb, currentColor 75%, grey)"));
-                combined_props.insert("min-height".into(), json!("80px"));
-                combined_props.insert("android-gravity".into(), json!("top"));
+                combined_props.insert("padding".into(), json!("12px").into());
+                combined_props.insert("border-radius".into(), json!("4px").into());
+                combined_props.insert("border-width".into(), json!("1px").into());
+                combined_props.insert("border-color".into(), json!("#cccccc").into());
+                combined_props.insert("background-color".into(), json!("#ffffff").into());
+                combined_props.insert("color".into(), json!("#000000").into());
+                combined_props.insert("placeholder-color".into(), json!("color-mix(in srgb, currentColor 75%, grey)").into());
+                combined_props.insert("min-height".into(), json!("80px").into());
+                combined_props.insert("android-gravity".into(), json!("top").into());
             }
             "button" => {
-                combined_props.insert("padding-vertical".into(), json!("8px"));
-                combined_props.insert("padding-horizontal".into(), json!("16px"));
-                combined_props.insert("border-radius".into(), json!("4px"));
-                combined_props.insert("background-color".into(), json!("#2196F3"));
-                combined_props.insert("color".into(), json!("#ffffff"));
-                combined_props.insert("android-gravity".into(), json!("center"));
+                combined_props.insert("padding-vertical".into(), json!("8px").into());
+                combined_props.insert("padding-horizontal".into(), json!("16px").into());
+                combined_props.insert("border-radius".into(), json!("4px").into());
+                combined_props.insert("background-color".into(), json!("#2196F3").into());
+                combined_props.insert("color".into(), json!("#ffffff").into());
+                combined_props.insert("android-gravity".into(), json!("center").into());
             }
             _ => {}
         }
@@ -469,13 +622,17 @@ impl State {
         }
 
         // 3. Apply class styles (overwrites selector)
+        let mut child_utilities = Vec::new();
+        let mut gradient_utilities = Vec::new();
         for class in classes {
             // Normalize input: strip leading dot if present (Android may pass ".bg-primary" as selector format)
-            let normalized_class = if class.starts_with('.') {
-                class[1..].to_string()
-            } else {
-                class.clone()
-            };
+            let normalized_class = class.strip_prefix('.').unwrap_or(class).to_string();
+            if !animation::motion_variant_applies(&normalized_class, self.reduced_motion) {
+                continue;
+            }
+            if direction::direction_variant(&normalized_class).is_some_and(|d| d != self.layout_direction) {
+                continue;
+            }
             
             let (_bp, _hover, base) = parse_prefixed_class(&normalized_class);
             // Prefer base selector match from theme
@@ -484,6 +641,24 @@ impl State {
                 merge_props(&mut combined_props, props);
                 continue;
             }
+            // Child utilities become parent-level hints, applied after the merge
+            if let Some(child) = children::parse_child_utility(&base, &vars) {
+                child_utilities.push(child);
+                continue;
+            }
+            // Gradient utilities only make sense together; composed after the merge
+            if let Some(gradient) = gradient::parse_gradient_utility(&base, &vars) {
+                gradient_utilities.push(gradient);
+                continue;
+            }
+            if let Some(animation) = base.strip_prefix("animate-").and_then(|name| animation::animate_utility(name, &vars, &keyframes)) {
+                property::merge_property(&mut combined_props, "animation", json!(animation).into());
+                continue;
+            }
+            if let Some(props) = base.strip_prefix("font-").and_then(|name| fonts::font_utility(name, &font_registry)) {
+                merge_props(&mut combined_props, &props);
+                continue;
+            }
             // Dynamic mapping for base class
             if let Some(dynamic_props) = dynamic_css_properties_for_class(&base, &vars) {
                 merge_props(&mut combined_props, &dynamic_props);
@@ -499,6 +674,10 @@ impl State {
         }
 
         merge_android_props(&mut out, &combined_props, &vars);
+        for child in &child_utilities {
+            child.apply_android_hints(&mut out, &vars);
+        }
+        gradient::apply_android_gradient(&mut out, &gradient_utilities, &vars);
         
         // CSS semantics: display: flex defaults to flexDirection: row
         if let Some(display) = out.get("display") {
@@ -541,14 +720,57 @@ impl State {
         let mut styles = self.android_base_styles(selector, classes);
         
         let density = self.display_density;
-        let scaled_density = self.scaled_density;
+        let mut units = self.unit_context();
+        let rtl = self.layout_direction == direction::LayoutDirection::Rtl;
 
         // Convert flexDirection to Android orientation EARLY so layout-dependent props (like gap) can use it
         if let Some(flex_dir) = styles.get("flexDirection") {
             let orientation = if flex_dir.as_str() == Some("row") { "horizontal" } else { "vertical" };
             styles.shift_insert(0, "androidOrientation".to_string(), serde_json::json!(orientation));
         }
+
+        // Resolve the font size first: em and unitless line heights are relative to it.
+        // Text uses scaled density for accessibility.
+        if let Some(font_dp) = styles.get("fontSize").and_then(|v| units.font_size_dp(v)) {
+            units.font_size = font_dp;
+            styles.insert("fontSize".to_string(), serde_json::json!(units.text_px(font_dp)));
+        }
+        if let Some(line_height) = styles.get("lineHeight").and_then(|v| units.line_height_px(v)) {
+            styles.insert("lineHeight".to_string(), serde_json::json!(line_height.round()));
+        }
         
+        // Logical properties become Android start/end attributes, which the view resolves
+        // against its own layout direction
+        direction::apply_android_logical(&mut styles);
+        if let Some(dir) = styles.shift_remove("direction") {
+            styles.insert("androidLayoutDirection".to_string(), dir);
+        }
+
+        // Elliptical corners ("8px 4px") become GradientDrawable corner radii (x, y per corner,
+        // clockwise from top-left); each corner key keeps its horizontal radius. A logical
+        // corner overrides the physical corner it lands on.
+        let corners = ["borderTopLeftRadius", "borderTopRightRadius", "borderBottomRightRadius", "borderBottomLeftRadius"];
+        let corners: Vec<&str> = corners
+            .into_iter()
+            .zip(direction::logical_corners(rtl))
+            .map(|(physical, logical)| if styles.contains_key(logical) { logical } else { physical })
+            .collect();
+        let is_elliptical = |v: &serde_json::Value| v.as_str().is_some_and(|s| s.split_whitespace().count() == 2);
+        if corners.iter().any(|c| styles.get(*c).is_some_and(is_elliptical)) {
+            let mut radii = Vec::new();
+            for corner in corners {
+                let value = styles.get(corner).cloned().unwrap_or(json!(0));
+                let (h, v) = match value.as_str().and_then(|s| s.split_once(' ')) {
+                    Some((h, v)) => (json!(h), json!(v.trim())),
+                    None => (value.clone(), value),
+                };
+                radii.push(units.to_px(&h).unwrap_or(json!(0)));
+                radii.push(units.to_px(&v).unwrap_or(json!(0)));
+                styles.insert(corner.to_string(), h);
+            }
+            styles.insert("androidCornerRadii".to_string(), json!(radii));
+        }
+
         // Convert all dimension properties to pixels
         let dimension_props = [
             "width", "height", "minWidth", "minHeight", "maxWidth", "maxHeight",
@@ -556,27 +778,39 @@ impl State {
             "paddingHorizontal", "paddingVertical",
             "margin", "marginTop", "marginBottom", "marginLeft", "marginRight",
             "marginHorizontal", "marginVertical",
-            "borderRadius", "borderWidth", "borderTopWidth", "borderBottomWidth",
-            "borderLeftWidth", "borderRightWidth",
-            "gap", "rowGap", "columnGap", "elevation", "lineHeight", "letterSpacing"
+            "borderRadius", "borderTopLeftRadius", "borderTopRightRadius", "borderBottomLeftRadius", "borderBottomRightRadius",
+            "borderWidth", "borderTopWidth", "borderBottomWidth",
+            "borderLeftWidth", "borderRightWidth", "outlineWidth", "outlineOffset",
+            "top", "right", "bottom", "left",
+            "paddingStart", "paddingEnd", "marginStart", "marginEnd", "start", "end",
+            "borderStartWidth", "borderEndWidth",
+            "borderTopStartRadius", "borderTopEndRadius", "borderBottomStartRadius", "borderBottomEndRadius",
+            "gap", "rowGap", "columnGap", "elevation", "letterSpacing", "flexBasis",
+            "androidChildSpacingHorizontal", "androidChildSpacingVertical",
+            "androidDividerHorizontal", "androidDividerVertical"
         ];
         
         for prop in &dimension_props {
             if let Some(value) = styles.get(*prop).cloned() {
-                if let Some(converted) = parse_and_convert_to_px(&value, density) {
+                if let Some(converted) = units.to_px(&value) {
                     styles.insert(prop.to_string(), converted);
                 }
             }
         }
-        
-        // Convert font sizes (use scaled density for accessibility)
-        if let Some(font_size) = styles.get("fontSize").cloned() {
-            if let Some(serde_json::Value::Number(n)) = parse_and_convert_to_px(&font_size, density).as_ref() {
-                // For text, use scaled density for accessibility
-                let sp_value = n.as_f64().unwrap_or(14.0) as f32 / density * scaled_density;
-                styles.insert("fontSize".to_string(), serde_json::json!(sp_value));
+
+        // Math expressions that survived conversion can't be used natively; report why
+        let mut unresolved = Vec::new();
+        for prop in dimension_props.iter().chain(&["fontSize", "lineHeight"]) {
+            if let Some(value) = styles.get(*prop).and_then(|v| v.as_str()).filter(|v| units::is_math_function(v)) {
+                if let Err(reason) = units.eval_math(value) {
+                    log::warn!("[android_styles_for] cannot resolve {}: {} ({})", prop, value, reason);
+                    unresolved.push(json!({ "property": prop, "value": value, "reason": reason }));
+                }
             }
         }
+        if !unresolved.is_empty() {
+            styles.insert("androidUnresolved".to_string(), json!(unresolved));
+        }
         
         // Convert flexWrap to Android-friendly format
         if let Some(flex_wrap) = styles.get("flexWrap") {
@@ -585,6 +819,35 @@ impl State {
             }
         }
 
+        // display:none / visibility map to View visibility; sr-only content stays in the
+        // accessibility tree without being drawn
+        let display_none = styles.get("display").and_then(|v| v.as_str()) == Some("none");
+        let visibility = match styles.get("visibility").and_then(|v| v.as_str()) {
+            _ if display_none => Some("gone"),
+            Some("hidden") => Some("invisible"),
+            Some("collapse") => Some("gone"),
+            Some("visible") => Some("visible"),
+            _ => None,
+        };
+        if let Some(visibility) = visibility {
+            styles.insert("androidVisibility".to_string(), json!(visibility));
+        }
+        if styles.get("clip").and_then(|v| v.as_str()).is_some_and(|c| c.replace(' ', "") == "rect(0,0,0,0)") {
+            styles.insert("androidAccessibilityOnly".to_string(), json!(true));
+        }
+        if styles.get("pointerEvents").and_then(|v| v.as_str()) == Some("none") {
+            styles.insert("androidClickable".to_string(), json!(false));
+            styles.insert("androidFocusable".to_string(), json!(false));
+        }
+        match styles.get("userSelect").and_then(|v| v.as_str()) {
+            Some("none") => { styles.insert("androidTextSelectable".to_string(), json!(false)); }
+            Some("text") | Some("all") => { styles.insert("androidTextSelectable".to_string(), json!(true)); }
+            _ => {}
+        }
+        if let Some(touch) = styles.get("touchAction").and_then(|v| v.as_str()).filter(|t| *t != "auto") {
+            styles.insert("androidTouchAction".to_string(), json!(touch.replace('-', "_")));
+        }
+
         // Map opacity to androidAlpha
         if let Some(opacity) = styles.get("opacity").cloned() {
             styles.insert("androidAlpha".to_string(), opacity);
@@ -595,17 +858,34 @@ impl State {
 
         // Convert alignItems (cross-axis) to Android gravity equivalents
         if let Some(align_items) = styles.get("alignItems") {
-            let part = match align_items.as_str() {
-                Some("center") => if is_horizontal { "center_vertical" } else { "center_horizontal" },
-                Some("flex-start") | Some("start") => if is_horizontal { "top" } else { "start" },
-                Some("flex-end") | Some("end") => if is_horizontal { "bottom" } else { "end" },
-                Some("stretch") => if is_horizontal { "fill_vertical" } else { "fill_horizontal" },
-                _ => ""
-            };
+            let part = cross_axis_gravity(align_items.as_str().unwrap_or(""), is_horizontal);
             if !part.is_empty() {
                 gravity_parts.push(part);
             }
         }
+
+        // alignSelf depends on the parent's orientation, which isn't known here: give the
+        // child's layout_gravity for both
+        if let Some(align_self) = styles.get("alignSelf").and_then(|v| v.as_str()) {
+            let (in_row, in_column) = (cross_axis_gravity(align_self, true), cross_axis_gravity(align_self, false));
+            if !in_row.is_empty() {
+                styles.insert("androidSelfGravity".to_string(), json!({ "horizontal": in_row, "vertical": in_column }));
+            }
+        }
+        if let Some(align_content) = styles.get("alignContent").and_then(|v| v.as_str()) {
+            let value = match align_content {
+                "flex-start" | "start" => "flex_start",
+                "flex-end" | "end" => "flex_end",
+                "center" => "center",
+                "space-between" => "space_between",
+                "space-around" => "space_around",
+                "stretch" | "normal" => "stretch",
+                _ => "",
+            };
+            if !value.is_empty() {
+                styles.insert("androidAlignContent".to_string(), json!(value));
+            }
+        }
         
         // Convert justifyContent (main-axis) to Android gravity equivalents
         if let Some(justify) = styles.get("justifyContent") {
@@ -642,30 +922,42 @@ impl State {
             styles.insert("androidGravity".to_string(), serde_json::json!(gravity));
         }
 
-        // Handle border shorthand: "1px solid #color"
-        if let Some(serde_json::Value::String(border)) = styles.get("border").cloned() {
-            let parts: Vec<&str> = border.split_whitespace().collect();
-            for part in parts {
-                if part.ends_with("px") {
-                    if let Ok(w) = part.trim_end_matches("px").parse::<f32>() {
-                        styles.insert("borderWidth".to_string(), serde_json::json!(dp_to_px(w, density)));
-                    }
-                } else if part.starts_with('#') {
-                    styles.insert("borderColor".to_string(), serde_json::json!(part));
-                }
+        // Box and text shadows: elevation, shadow colors and structured layers.
+        // Variables were already resolved by android_base_styles.
+        let current_color = styles.get("color").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let no_vars = IndexMap::new();
+        if let Some(serde_json::Value::String(shadow)) = styles.get("boxShadow").cloned() {
+            let layers = shadow::parse_shadow_list(&shadow, &units);
+            if !layers.is_empty() {
+                shadow::apply_box_shadow(&mut styles, &layers, &units, current_color.as_deref(), &no_vars);
+            }
+        }
+        if let Some(serde_json::Value::String(shadow)) = styles.get("textShadow").cloned() {
+            let layers = shadow::parse_shadow_list(&shadow, &units);
+            if !layers.is_empty() {
+                shadow::apply_text_shadow(&mut styles, &layers, &units, current_color.as_deref(), &no_vars);
             }
         }
 
-        // Map boxShadow to elevation
-        if let Some(serde_json::Value::String(shadow)) = styles.get("boxShadow").cloned() {
-            if !shadow.is_empty() {
-                let elevation = if shadow.contains("20px") { 24 }
-                               else if shadow.contains("15px") { 16 }
-                               else if shadow.contains("10px") { 8 }
-                               else { 4 };
-                styles.insert("elevation".to_string(), serde_json::json!(dp_to_px(elevation as f32, density)));
+        // Keyframe animations become a structured descriptor with per-property tracks
+        if let Some(spec) = styles.get("animation").and_then(|v| v.as_str()).and_then(animation::parse_animation) {
+            if let Some(frames) = self.effective_keyframes().get(&spec.name) {
+                let (_, vars) = self.effective_theme_all();
+                let anim = animation::android_animation(&spec, frames, &units, &vars, current_color.as_deref());
+                styles.insert("androidAnimation".to_string(), anim);
             }
         }
+        // Transitions are replaced by a single normalized spec the renderer can animate with
+        let transition = animation::android_transition(&styles);
+        for key in [
+            "transition", "transitionProperty", "transitionDuration", "transitionDelay", "transitionTimingFunction",
+            "--tw-duration", "--tw-ease",
+        ] {
+            styles.shift_remove(key);
+        }
+        if let Some(transition) = transition {
+            styles.insert("androidTransition".to_string(), transition);
+        }
 
         // Convert overflow-x/overflow-y to Android scrolling hints
         if let Some(overflow_x) = styles.get("overflowX") {
@@ -679,10 +971,12 @@ impl State {
             }
         }
         
-        // Convert textAlign to Android gravity
+        // Convert textAlign to Android gravity. Physical left/right stay physical in RTL layouts.
         if let Some(text_align) = styles.get("textAlign") {
             let gravity = match text_align.as_str() {
                 Some("center") => "center_horizontal",
+                Some("right") if rtl => "right",
+                Some("left") if rtl => "left",
                 Some("right") | Some("end") => "end",
                 Some("left") | Some("start") => "start",
                 _ => ""
@@ -707,6 +1001,28 @@ impl State {
             }
         }
 
+        // objectPosition as 0..1 alignment of the content in its box; contained images can
+        // use ImageView's own start/end scale types
+        if let Some((x, y)) = styles.get("objectPosition").and_then(|v| v.as_str()).and_then(parse_object_position) {
+            styles.insert("androidObjectPosition".to_string(), json!({ "x": x, "y": y }));
+            if styles.get("androidScaleType").and_then(|v| v.as_str()) == Some("fit_center") {
+                let centered = x == 0.5 && y == 0.5;
+                if !centered && x <= 0.5 && y <= 0.5 {
+                    styles.insert("androidScaleType".to_string(), json!("fit_start"));
+                } else if !centered && x >= 0.5 && y >= 0.5 {
+                    styles.insert("androidScaleType".to_string(), json!("fit_end"));
+                }
+            }
+        }
+
+        // aspect-ratio: width / height for the layout, plus a ConstraintLayout-style ratio string
+        if let Some((w, h)) = styles.get("aspectRatio").and_then(parse_aspect_ratio) {
+            styles.insert(
+                "androidAspectRatio".to_string(),
+                json!({ "ratio": ((w / h) as f64 * 1e4).round() / 1e4, "dimensionRatio": format!("{}:{}", w, h) }),
+            );
+        }
+
         // Handle full width/height
         if let Some(h) = styles.get("height").cloned() {
             if h.as_str() == Some("100%") {
@@ -731,7 +1047,29 @@ impl State {
                 styles.insert("height".to_string(), serde_json::json!("wrap_content"));
             }
         }
+
+        // Flex item hints for the parent: layout weight from grow, whether the child may shrink,
+        // and its position in the parent's ordering
+        let (grow, shrink) = flex_factors(&styles);
+        if let Some(grow) = grow {
+            styles.insert("androidLayoutWeight".to_string(), json!(grow));
+        }
+        if let Some(shrink) = shrink {
+            styles.insert("androidFlexShrink".to_string(), json!(shrink));
+        }
+        if let Some(order) = styles.get("order").and_then(|v| v.as_i64().or_else(|| v.as_str()?.trim().parse().ok())) {
+            styles.insert("androidOrder".to_string(), json!(order));
+        }
         
+        // Concrete typeface (font resource, asset or system family) for the weight and style
+        if let Some(family) = styles.get("fontFamily").and_then(|v| v.as_str()) {
+            let weight = styles.get("fontWeight").map(fonts::parse_weight).unwrap_or(400);
+            let style = styles.get("fontStyle").and_then(|v| v.as_str()).unwrap_or("normal");
+            if let Some(typeface) = fonts::android_typeface(family, weight, style, &self.effective_fonts()) {
+                styles.insert("androidTypeface".to_string(), typeface);
+            }
+        }
+
         // Convert fontWeight to Android typeface style
         if let Some(font_weight) = styles.get("fontWeight") {
             let is_bold = match font_weight {
@@ -746,21 +1084,22 @@ impl State {
                 styles.insert("androidTypefaceStyle".to_string(), serde_json::json!("bold"));
             }
         }
-        
-        // Convert boxShadow to elevation
-        if let Some(box_shadow) = styles.get("boxShadow") {
-            if let Some(shadow_str) = box_shadow.as_str() {
-                if !shadow_str.is_empty() {
-                    let elevation_dp = if shadow_str.contains("20px") { 24.0 }
-                    else if shadow_str.contains("15px") { 16.0 }
-                    else if shadow_str.contains("10px") { 8.0 }
-                    else if shadow_str.contains("5px") { 4.0 }
-                    else { 4.0 };
-                    styles.insert("elevation".to_string(), serde_json::json!(dp_to_px(elevation_dp, density)));
-                }
+
+        // Opt-in minimum touch target for tappable elements
+        if let Some(target) = self.accessibility_policy().min_touch_target {
+            if accessibility::is_tappable(selector, &styles) {
+                accessibility::apply_touch_target(&mut styles, dp_to_px(target, density) as i64);
             }
         }
-        
+
+        // Colors were resolved to CSS hex; Android expects alpha first
+        color::normalize_android_colors(&mut styles);
+
+        // Composite drawable alongside the flat keys
+        if let Some(background) = background::android_background(&styles, rtl) {
+            styles.insert("androidBackground".to_string(), background);
+        }
+
         styles
     }
 
@@ -768,13 +1107,17 @@ impl State {
 
     // Build the inheritance chain from current theme upward via `inherits` and default fallback
     fn theme_chain(&self) -> Vec<String> {
+        self.theme_chain_from(&self.current_theme)
+    }
+
+    fn theme_chain_from(&self, start: &str) -> Vec<String> {
         let mut chain = Vec::new();
         // Resolve base names
         let default_name = if self.themes.contains_key(&self.default_theme) {
             self.default_theme.clone()
         } else if let Some((k, _)) = self.themes.first() { k.clone() } else { return chain };
-        let mut current_name = if self.themes.contains_key(&self.current_theme) {
-            self.current_theme.clone()
+        let mut current_name = if self.themes.contains_key(start) {
+            start.to_string()
         } else { default_name.clone() };
         // push child first
         let mut seen: IndexSet<String> = IndexSet::new();
@@ -798,10 +1141,14 @@ impl State {
     // Compute effective selectors + variables + breakpoints with inheritance.
     // Child overrides parent/default on conflicts (expected for "inherits").
     fn effective_theme_all(&self) -> (SelectorStyles, IndexMap<String, String>) {
+        self.effective_theme_all_for(&self.current_theme)
+    }
+
+    fn effective_theme_all_for(&self, theme: &str) -> (SelectorStyles, IndexMap<String, String>) {
         let mut selectors: SelectorStyles = SelectorStyles::new();
         let mut vars: IndexMap<String, String> = IndexMap::new();
         // Merge default -> parents -> child so child wins on conflicts
-        let chain = self.theme_chain();
+        let chain = self.theme_chain_from(theme);
         for name in chain.into_iter().rev() {
             if let Some(entry) = self.themes.get(&name) {
                 // merge selectors: later (child) overrides earlier (parent/default)
@@ -828,6 +1175,71 @@ impl State {
         (selectors, vars)
     }
 
+    /// WCAG contrast audit of every theme, with inheritance applied.
+    /// Checks each theme's `contrast_pairs` and every selector setting both a text and background color.
+    pub fn contrast_audit(&self) -> Vec<contrast::ThemeContrastReport> {
+        self.themes
+            .keys()
+            .map(|name| {
+                let (selectors, vars) = self.effective_theme_all_for(name);
+                contrast::audit_theme(name, &selectors, &vars, &self.effective_contrast_pairs(name))
+            })
+            .collect()
+    }
+
+    /// Generate a dark counterpart of theme `source` that inherits it (see `ThemeEntry::derive_dark`).
+    /// Uses the source's effective (inherited) variables and contrast pairs.
+    /// Returns `None` if the theme doesn't exist; the caller registers the result under a name of its choosing.
+    pub fn derive_dark_theme(&self, source: &str) -> Option<ThemeEntry> {
+        let entry = self.themes.get(source)?;
+        let (_, vars) = self.effective_theme_all_for(source);
+        let pairs = self.effective_contrast_pairs(source);
+        Some(dark::derive_dark_theme(source, entry, &vars, &pairs, contrast::AA_NORMAL))
+    }
+
+    fn effective_contrast_pairs(&self, theme: &str) -> IndexMap<String, String> {
+        let mut pairs = IndexMap::new();
+        for name in self.theme_chain_from(theme).into_iter().rev() {
+            if let Some(entry) = self.themes.get(&name) {
+                pairs.extend(entry.contrast_pairs.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+        pairs
+    }
+
+    /// Accessibility policy merged across the theme chain (child values win).
+    fn accessibility_policy(&self) -> accessibility::AccessibilityPolicy {
+        let mut policy = accessibility::AccessibilityPolicy::default();
+        for name in self.theme_chain() {
+            if let Some(entry) = self.themes.get(&name) {
+                policy.inherit(&entry.accessibility);
+            }
+        }
+        policy
+    }
+
+    /// Built-in keyframes overlaid with the theme chain's (child overrides parent).
+    fn effective_keyframes(&self) -> IndexMap<String, animation::Keyframes> {
+        let mut keyframes = animation::builtin_keyframes();
+        for name in self.theme_chain().into_iter().rev() {
+            if let Some(entry) = self.themes.get(&name) {
+                keyframes.extend(entry.keyframes.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+        keyframes
+    }
+
+    /// Font registry across the theme chain (child overrides parent).
+    fn effective_fonts(&self) -> IndexMap<String, fonts::FontFamily> {
+        let mut registry = IndexMap::new();
+        for name in self.theme_chain().into_iter().rev() {
+            if let Some(entry) = self.themes.get(&name) {
+                registry.extend(entry.fonts.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+        registry
+    }
+
     // Effective breakpoints with inheritance; child overrides parent/default.
     pub fn effective_breakpoints(&self) -> IndexMap<String, String> {
         let mut bps: IndexMap<String, String> = IndexMap::new();
@@ -843,59 +1255,6 @@ impl State {
     }
 }
 
-fn split_tag_class_key(key: &str) -> Option<(String, String)> {
-    let mut it = key.splitn(2, '|');
-    let t = it.next()?.to_string();
-    let c = it.next()?.to_string();
-    if t.is_empty() || c.is_empty() { return None; }
-    Some((t, c))
-}
-
-fn strip_hover_suffix(selector: &str) -> (&str, bool) {
-    if let Some(stripped) = selector.strip_suffix(":hover") { (stripped, true) } else { (selector, false) }
-}
-
-fn should_emit_selector(sel: &str, used_tags: &IndexSet<String>, used_classes: &IndexSet<String>, used_tag_classes: &IndexSet<String>) -> bool {
-    // Optionally handle :hover suffix
-    let (base, _hover) = strip_hover_suffix(sel);
-
-    // tag-only
-    if is_simple_tag(base) {
-        return used_tags.contains(base) || used_tag_classes.iter().any(|k| k.split('|').next() == Some(base));
-    }
-
-    // .class-only
-    if let Some(class_name) = base.strip_prefix('.') {
-        // Normalize potential escaped class names as-is
-        return used_classes.contains(class_name) || used_tag_classes.iter().any(|k| k.ends_with(&format!("|{}", class_name)));
-    }
-
-    // tag.class
-    if let Some((tag, class_name)) = split_tag_class_selector(base) {
-        let key = format!("{}|{}", tag, class_name);
-        return used_tag_classes.contains(&key) || (used_tags.contains(&tag) && used_classes.contains(&class_name));
-    }
-
-    // Other complex selectors are currently ignored
-    false
-}
-
-fn is_simple_tag(s: &str) -> bool {
-    // Match simple HTML tag-ish identifiers
-    let mut chars = s.chars();
-    match chars.next() { Some(c) if c.is_ascii_alphabetic() => {}, _ => return false }
-    chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
-}
-
-fn split_tag_class_selector(s: &str) -> Option<(String, String)> {
-    // "tag.class" -> (tag, class)
-    let mut parts = s.splitn(2, '.');
-    let tag = parts.next()?.to_string();
-    let class_name = parts.next()?.to_string();
-    if tag.is_empty() || class_name.is_empty() { return None; }
-    Some((tag, class_name))
-}
-
 // wasm-bindgen exports (only when compiling to wasm32)
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
@@ -1003,9 +1362,14 @@ pub fn get_theme_list_json(state_json: &str) -> String {
     }
 }
 
-fn merge_props(into: &mut CssProps, from: &CssProps) {
-    for (k, v) in from.iter() {
-        into.insert(k.clone(), v.clone());
+/// Run the WCAG contrast audit over every theme in the state JSON.
+/// Returns an array of `{ theme, checks: [...] }` reports, or "[]" on error.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn audit_contrast_json(state_json: &str) -> String {
+    match serde_json::from_str::<State>(state_json) {
+        Ok(state) => serde_json::to_string(&state.contrast_audit()).unwrap_or_else(|_| "[]".to_string()),
+        _ => "[]".to_string(),
     }
 }
 
@@ -1013,13 +1377,26 @@ fn merge_props(into: &mut CssProps, from: &CssProps) {
 
 fn css_props_string(props: &CssProps, vars: &IndexMap<String, String>) -> String {
     let mut buf = String::new();
-    for (k, v) in props.iter() {
+    for (k, v) in lowering::lower_for_web(props).iter() {
         let key = crate::utils::kebab_case(k);
+        if let Some(s) = v.as_str() {
+            if let Some(longhands) = border::expand_border_shorthand(&key, &resolve_vars(s, vars)) {
+                for (lk, lv) in longhands {
+                    let lv = if lk.ends_with("-width") { value::web_value(&lv) } else { lv };
+                    buf.push_str(&format!("{}:{};", lk, lv));
+                }
+                continue;
+            }
+        }
         buf.push_str(&key);
         buf.push(':');
         let val = if v.is_string() {
             let s = v.as_str().unwrap();
-            resolve_vars(s, vars)
+            // Native units ("8dp 16dp", "18sp") and keywords (match_parent) aren't valid CSS
+            let resolved = color::resolve_color_functions(&resolve_vars(s, vars), vars);
+            let lowered = lowering::lower_value(&resolved);
+            // Values without variables or color functions serialize from the model parsed at load
+            if lowered == s { v.to_web() } else { value::web_value(lowered) }
         } else {
             v.to_string()
         };
@@ -1031,67 +1408,6 @@ fn css_props_string(props: &CssProps, vars: &IndexMap<String, String>) -> String
     buf
 }
 
-/// Parse var() references manually (replaces regex dependency)
-/// Matches: var(--name), var(name), with optional whitespace
-/// Supports alphanumeric, underscore, dot, and dash in variable names
-fn parse_var_references(input: &str) -> Vec<(usize, usize, String)> {
-    let mut results = Vec::new();
-    let bytes = input.as_bytes();
-    let mut i = 0;
-    
-    while i < bytes.len() {
-        // Look for "var("
-        if i + 4 <= bytes.len() && &bytes[i..i+4] == b"var(" {
-            let start = i;
-            i += 4;
-            
-            // Skip whitespace
-            while i < bytes.len() && (bytes[i] == b' ' || bytes[i] == b'\t' || bytes[i] == b'\n' || bytes[i] == b'\r') {
-                i += 1;
-            }
-            
-            // Check for optional -- prefix
-            let has_prefix = i + 2 <= bytes.len() && &bytes[i..i+2] == b"--";
-            if has_prefix {
-                i += 2;
-            }
-            
-            // Collect variable name: [a-zA-Z0-9_.-]+
-            let name_start = i;
-            while i < bytes.len() {
-                let c = bytes[i];
-                if (c >= b'a' && c <= b'z') || (c >= b'A' && c <= b'Z') || 
-                   (c >= b'0' && c <= b'9') || c == b'_' || c == b'.' || c == b'-' {
-                    i += 1;
-                } else {
-                    break;
-                }
-            }
-            
-            let name_end = i;
-            if name_start < name_end {
-                // Skip trailing whitespace
-                while i < bytes.len() && (bytes[i] == b' ' || bytes[i] == b'\t' || bytes[i] == b'\n' || bytes[i] == b'\r') {
-                    i += 1;
-                }
-                
-                // Check for closing )
-                if i < bytes.len() && bytes[i] == b')' {
-                    let end = i + 1;
-                    let var_name = std::str::from_utf8(&bytes[name_start..name_end])
-                        .unwrap_or("").to_string();
-                    results.push((start, end, var_name));
-                    i = end;
-                    continue;
-                }
-            }
-        }
-        i += 1;
-    }
-    
-    results
-}
-
 // Tailwind color palette - embedded from tailwind-colors.html
 static TAILWIND_COLORS: Lazy<IndexMap<&'static str, IndexMap<&'static str, &'static str>>> = Lazy::new(|| {
     let mut colors = IndexMap::new();
@@ -1253,55 +1569,6 @@ static TAILWIND_COLORS: Lazy<IndexMap<&'static str, IndexMap<&'static str, &'sta
     colors
 });
 
-fn resolve_vars(input: &str, vars: &IndexMap<String, String>) -> String {
-    let var_refs = parse_var_references(input);
-    
-    if var_refs.is_empty() {
-        // Fast path: no var() references, just check for $ prefix
-        if input.starts_with('$') {
-            if let Some(val) = vars.get(&input[1..]) {
-                return val.clone();
-            }
-        }
-        return input.to_string();
-    }
-    
-    // Replace var() references from right to left to preserve indices
-    let mut out = input.to_string();
-    for (start, end, var_name) in var_refs.iter().rev() {
-        if let Some(val) = vars.get(var_name) {
-            out.replace_range(*start..*end, val);
-        }
-    }
-    
-    // Also handle $ prefix for direct variable references
-    if out.starts_with('$') {
-        if let Some(val) = vars.get(&out[1..]) {
-            return val.clone();
-        }
-    }
-    
-    out
-}
-
-fn camel_case(name: &str) -> String {
-    let mut out = String::new();
-    let mut upper = false;
-    for ch in name.chars() {
-        if ch == '-' {
-            upper = true;
-            continue;
-        }
-        if upper {
-            out.extend(ch.to_uppercase());
-            upper = false;
-        } else {
-            out.push(ch);
-        }
-    }
-    out
-}
-
 fn css_value_to_android(
     value: &serde_json::Value,
     vars: &IndexMap<String, String>,
@@ -1315,13 +1582,8 @@ fn css_value_to_android(
     match value {
         serde_json::Value::String(s) => {
             let s2 = resolve_vars(s, vars);
-            let s3 = color::resolve_color(&s2, current_color, vars);
-            if let Some(n) = s3.strip_suffix("px") {
-                if let Ok(parsed) = n.trim().parse::<f64>() {
-                    return json!(parsed);
-                }
-            }
-            json!(s3)
+            // Units are kept: android_styles_for converts lengths with the unit engine
+            json!(color::resolve_color(&s2, current_color, vars))
         }
         _ => value.clone(),
     }
@@ -1347,13 +1609,46 @@ fn merge_android_props(
     }
 
     for (k, v) in css_props.iter() {
+        let prop = property::CssProperty::parse(k);
+        if !prop.supported_on(property::Platform::Native) {
+            log::debug!("[merge_android_props] dropping web-only property {}", k);
+            continue;
+        }
         let val = css_value_to_android(v, vars, current_color.as_deref());
+        property::clear_stale_aggregates(into, prop.id());
         
-        if k == "placeholder-color" || k == "placeholderColor" {
+        if prop.id() == "placeholder-color" {
             log::debug!("[merge_android_props] placeholder-color: input={:?} output={:?}", v, val);
         }
 
-        match k.as_str() {
+        if let Some(longhands) = v.as_str().and_then(|s| {
+            border::expand_border_shorthand(prop.id(), &resolve_vars(s, vars))
+        }) {
+            for (lk, lv) in longhands {
+                into.insert(camel_case(&lk), css_value_to_android(&json!(lv), vars, current_color.as_deref()));
+            }
+            continue;
+        }
+
+        // Per-side box shorthands ("8dp 16dp", "4px / 8px"); single values keep the aggregate keys below
+        let kebab = prop.id();
+        if let Some(resolved) = v.as_str().map(|s| resolve_vars(s, vars)) {
+            if shorthands::is_box_shorthand(kebab) && (kebab == "inset" || shorthands::is_multi_value(&resolved)) {
+                if let Some(longhands) = shorthands::expand_box_shorthand(kebab, &resolved) {
+                    // Aggregates from lower-priority sources no longer describe every side
+                    let aggregate = prop.camel();
+                    for stale in [aggregate.clone(), format!("{}Horizontal", aggregate), format!("{}Vertical", aggregate)] {
+                        into.shift_remove(&stale);
+                    }
+                    for (lk, lv) in longhands {
+                        into.insert(camel_case(&lk), css_value_to_android(&json!(lv), vars, current_color.as_deref()));
+                    }
+                    continue;
+                }
+            }
+        }
+
+        match prop.id() {
             "padding" => {
                 into.insert("paddingTop".to_string(), val.clone());
                 into.insert("paddingBottom".to_string(), val.clone());
@@ -1363,12 +1658,12 @@ fn merge_android_props(
                 into.insert("paddingVertical".to_string(), val.clone());
                 into.insert("padding".to_string(), val);
             }
-            "padding-horizontal" | "paddingHorizontal" => {
+            "padding-horizontal" => {
                 into.insert("paddingLeft".to_string(), val.clone());
                 into.insert("paddingRight".to_string(), val.clone());
                 into.insert("paddingHorizontal".to_string(), val);
             }
-            "padding-vertical" | "paddingVertical" => {
+            "padding-vertical" => {
                 into.insert("paddingTop".to_string(), val.clone());
                 into.insert("paddingBottom".to_string(), val.clone());
                 into.insert("paddingVertical".to_string(), val);
@@ -1382,17 +1677,17 @@ fn merge_android_props(
                 into.insert("marginVertical".to_string(), val.clone());
                 into.insert("margin".to_string(), val);
             }
-            "margin-horizontal" | "marginHorizontal" => {
+            "margin-horizontal" => {
                 into.insert("marginLeft".to_string(), val.clone());
                 into.insert("marginRight".to_string(), val.clone());
                 into.insert("marginHorizontal".to_string(), val);
             }
-            "margin-vertical" | "marginVertical" => {
+            "margin-vertical" => {
                 into.insert("marginTop".to_string(), val.clone());
                 into.insert("marginBottom".to_string(), val.clone());
                 into.insert("marginVertical".to_string(), val);
             }
-            "border-radius" | "borderRadius" => {
+            "border-radius" => {
                 into.insert("borderTopLeftRadius".to_string(), val.clone());
                 into.insert("borderTopRightRadius".to_string(), val.clone());
                 into.insert("borderBottomLeftRadius".to_string(), val.clone());
@@ -1401,7 +1696,7 @@ fn merge_android_props(
             }
             "background-color" => { into.insert("backgroundColor".to_string(), val); }
             "text-align" => { into.insert("textAlign".to_string(), val); }
-            "flex-direction" | "flexDirection" => {
+            "flex-direction" => {
                 let orientation = if val.as_str() == Some("column") || val.as_str() == Some("column-reverse") {
                     "vertical"
                 } else {
@@ -1411,222 +1706,362 @@ fn merge_android_props(
                 into.insert("flexDirection".to_string(), val);
             }
             _ => {
-                into.insert(camel_case(k), val);
+                into.insert(prop.camel(), val);
             }
         }
     }
 }
 
-fn dynamic_css_properties_for_class(class: &str, vars: &IndexMap<String, String>) -> Option<CssProps> {
+/// Default duration and easing for `transition*` utilities. They read the `--tw-duration` /
+/// `--tw-ease` set by `duration-*` / `ease-*`, so explicit utilities win in any class order.
+fn insert_transition_defaults(props: &mut CssProps) {
+    props.insert("transition-duration".into(), json!("var(--tw-duration, 150ms)").into());
+    props.insert("transition-timing-function".into(), json!("var(--tw-ease, ease-in-out)").into());
+}
+
+fn dynamic_css_properties_for_class(class: &str, vars: &IndexMap<String, String>) -> Option<CssProps> {
     // Display utilities
     match class {
-        "block" => { let mut p = CssProps::new(); p.insert("display".into(), json!("block")); return Some(p); }
-        "inline-block" => { let mut p = CssProps::new(); p.insert("display".into(), json!("inline-block")); return Some(p); }
-        "inline" => { let mut p = CssProps::new(); p.insert("display".into(), json!("inline")); return Some(p); }
-        "inline-flex" => { let mut p = CssProps::new(); p.insert("display".into(), json!("inline-flex")); return Some(p); }
-        "grid" => { let mut p = CssProps::new(); p.insert("display".into(), json!("grid")); return Some(p); }
-        "hidden" => { let mut p = CssProps::new(); p.insert("display".into(), json!("none")); return Some(p); }
+        "block" => { let mut p = CssProps::new(); p.insert("display".into(), json!("block").into()); return Some(p); }
+        "inline-block" => { let mut p = CssProps::new(); p.insert("display".into(), json!("inline-block").into()); return Some(p); }
+        "inline" => { let mut p = CssProps::new(); p.insert("display".into(), json!("inline").into()); return Some(p); }
+        "inline-flex" => { let mut p = CssProps::new(); p.insert("display".into(), json!("inline-flex").into()); return Some(p); }
+        "grid" => { let mut p = CssProps::new(); p.insert("display".into(), json!("grid").into()); return Some(p); }
+        "hidden" => { let mut p = CssProps::new(); p.insert("display".into(), json!("none").into()); return Some(p); }
         _ => {}
     }
+    // Visibility and interactivity
+    match class {
+        "visible" => { let mut p = CssProps::new(); p.insert("visibility".into(), json!("visible").into()); return Some(p); }
+        "invisible" => { let mut p = CssProps::new(); p.insert("visibility".into(), json!("hidden").into()); return Some(p); }
+        "collapse" => { let mut p = CssProps::new(); p.insert("visibility".into(), json!("collapse").into()); return Some(p); }
+        "sr-only" | "not-sr-only" => {
+            let sr = class == "sr-only";
+            let mut p = CssProps::new();
+            p.insert("position".into(), json!(if sr { "absolute" } else { "static" }).into());
+            p.insert("width".into(), json!(if sr { "1px" } else { "auto" }).into());
+            p.insert("height".into(), json!(if sr { "1px" } else { "auto" }).into());
+            p.insert("padding".into(), json!("0").into());
+            p.insert("margin".into(), json!(if sr { "-1px" } else { "0" }).into());
+            p.insert("overflow".into(), json!(if sr { "hidden" } else { "visible" }).into());
+            p.insert("clip".into(), json!(if sr { "rect(0, 0, 0, 0)" } else { "auto" }).into());
+            p.insert("white-space".into(), json!(if sr { "nowrap" } else { "normal" }).into());
+            if sr {
+                p.insert("border-width".into(), json!("0").into());
+            }
+            return Some(p);
+        }
+        "pointer-events-none" => { let mut p = CssProps::new(); p.insert("pointer-events".into(), json!("none").into()); return Some(p); }
+        "pointer-events-auto" => { let mut p = CssProps::new(); p.insert("pointer-events".into(), json!("auto").into()); return Some(p); }
+        _ => {}
+    }
+    if let Some(rest) = class.strip_prefix("select-") {
+        if matches!(rest, "none" | "text" | "all" | "auto") {
+            let mut p = CssProps::new();
+            p.insert("user-select".into(), json!(rest).into());
+            return Some(p);
+        }
+    }
+    if let Some(rest) = class.strip_prefix("touch-") {
+        if matches!(rest, "auto" | "none" | "manipulation" | "pan-x" | "pan-y" | "pinch-zoom") {
+            let mut p = CssProps::new();
+            p.insert("touch-action".into(), json!(rest).into());
+            return Some(p);
+        }
+    }
     // Flexbox shorthands
     match class {
-        "flex" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex")); return Some(p); }
-        "flex-row" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex")); p.insert("flexDirection".into(), json!("row")); return Some(p); }
-        "flex-col" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex")); p.insert("flexDirection".into(), json!("column")); return Some(p); }
-        "flex-wrap" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex")); p.insert("flex-wrap".into(), json!("wrap")); return Some(p); }
-        "flex-nowrap" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex")); p.insert("flex-wrap".into(), json!("nowrap")); return Some(p); }
-        "flex-wrap-reverse" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex")); p.insert("flex-wrap".into(), json!("wrap-reverse")); return Some(p); }
-        "flex-1" => { let mut p = CssProps::new(); p.insert("flex".into(), json!(1)); return Some(p); }
-        "w-full" => { let mut p = CssProps::new(); p.insert("width".into(), json!("match_parent")); return Some(p); }
-        "h-full" => { let mut p = CssProps::new(); p.insert("height".into(), json!("match_parent")); return Some(p); }
+        "flex" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex").into()); return Some(p); }
+        "flex-row" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex").into()); p.insert("flexDirection".into(), json!("row").into()); return Some(p); }
+        "flex-col" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex").into()); p.insert("flexDirection".into(), json!("column").into()); return Some(p); }
+        "flex-wrap" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex").into()); p.insert("flex-wrap".into(), json!("wrap").into()); return Some(p); }
+        "flex-nowrap" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex").into()); p.insert("flex-wrap".into(), json!("nowrap").into()); return Some(p); }
+        "flex-wrap-reverse" => { let mut p = CssProps::new(); p.insert("display".into(), json!("flex").into()); p.insert("flex-wrap".into(), json!("wrap-reverse").into()); return Some(p); }
+        "flex-1" => { let mut p = CssProps::new(); p.insert("flex".into(), json!(1).into()); return Some(p); }
+        "flex-auto" => { let mut p = CssProps::new(); p.insert("flex".into(), json!("1 1 auto").into()); return Some(p); }
+        "flex-initial" => { let mut p = CssProps::new(); p.insert("flex".into(), json!("0 1 auto").into()); return Some(p); }
+        "flex-none" => { let mut p = CssProps::new(); p.insert("flex".into(), json!("none").into()); return Some(p); }
+        "grow" | "flex-grow" => { let mut p = CssProps::new(); p.insert("flex-grow".into(), json!(1).into()); return Some(p); }
+        "grow-0" | "flex-grow-0" => { let mut p = CssProps::new(); p.insert("flex-grow".into(), json!(0).into()); return Some(p); }
+        "shrink" | "flex-shrink" => { let mut p = CssProps::new(); p.insert("flex-shrink".into(), json!(1).into()); return Some(p); }
+        "shrink-0" | "flex-shrink-0" => { let mut p = CssProps::new(); p.insert("flex-shrink".into(), json!(0).into()); return Some(p); }
+        "w-full" => { let mut p = CssProps::new(); p.insert("width".into(), json!("match_parent").into()); return Some(p); }
+        "h-full" => { let mut p = CssProps::new(); p.insert("height".into(), json!("match_parent").into()); return Some(p); }
         _ => {}
     }
     if let Some(value) = class.strip_prefix("z-") {
         if let Ok(z) = value.parse::<i32>() {
             let mut p = CssProps::new();
-            p.insert("elevation".into(), json!(z));
+            p.insert("elevation".into(), json!(z).into());
+            return Some(p);
+        }
+        if let Some(token) = theme_token(vars, "zIndex", value) {
+            let mut p = CssProps::new();
+            p.insert("elevation".into(), token.trim().parse::<i32>().map(|z| json!(z)).unwrap_or_else(|_| json!(token)).into());
+            return Some(p);
+        }
+    }
+    if let Some(val) = class.strip_prefix("basis-") {
+        return width_like_props("flex-basis", val);
+    }
+    if let Some(rest) = class.strip_prefix("order-") {
+        let order = match rest {
+            "first" => -9999,
+            "last" => 9999,
+            "none" => 0,
+            n => n.parse::<i32>().ok()?,
+        };
+        let mut p = CssProps::new();
+        p.insert("order".into(), json!(order).into());
+        return Some(p);
+    }
+    if let Some(rest) = class.strip_prefix("self-") {
+        let v = match rest { "auto" => "auto", "start" => "flex-start", "end" => "flex-end", "center" => "center", "stretch" => "stretch", "baseline" => "baseline", _ => return None };
+        let mut p = CssProps::new();
+        p.insert("align-self".into(), json!(v).into());
+        return Some(p);
+    }
+    if let Some(rest) = class.strip_prefix("content-") {
+        let v = match rest {
+            "normal" => "normal", "start" => "flex-start", "end" => "flex-end", "center" => "center", "stretch" => "stretch",
+            "between" => "space-between", "around" => "space-around", "evenly" => "space-evenly", "baseline" => "baseline",
+            _ => return None,
+        };
+        let mut p = CssProps::new();
+        p.insert("align-content".into(), json!(v).into());
+        return Some(p);
+    }
+    // place-* sets the align and justify longhands together
+    for (prefix, align, justify) in [
+        ("place-content-", "align-content", "justify-content"),
+        ("place-items-", "align-items", "justify-items"),
+        ("place-self-", "align-self", "justify-self"),
+    ] {
+        if let Some(rest) = class.strip_prefix(prefix) {
+            let v = match rest {
+                "start" => "start", "end" => "end", "center" => "center", "stretch" => "stretch", "auto" => "auto", "baseline" => "baseline",
+                "between" => "space-between", "around" => "space-around", "evenly" => "space-evenly",
+                _ => return None,
+            };
+            let mut p = CssProps::new();
+            p.insert(align.into(), json!(v).into());
+            p.insert(justify.into(), json!(v).into());
+            return Some(p);
+        }
+    }
+    for (prefix, prop) in [("justify-items-", "justify-items"), ("justify-self-", "justify-self")] {
+        if let Some(rest) = class.strip_prefix(prefix) {
+            let v = match rest {
+                "start" | "end" | "center" | "stretch" | "auto" => rest,
+                "normal" if prop == "justify-items" => rest,
+                _ => return None,
+            };
+            let mut p = CssProps::new();
+            p.insert(prop.into(), json!(v).into());
             return Some(p);
         }
     }
     if let Some(rest) = class.strip_prefix("items-") {
         let mut p = CssProps::new();
         let v = match rest { "start" => "flex-start", "end" => "flex-end", "center" => "center", "stretch" => "stretch", other => other };
-        p.insert("align-items".into(), json!(v));
+        p.insert("align-items".into(), json!(v).into());
         return Some(p);
     }
     if let Some(rest) = class.strip_prefix("justify-") {
         let mut p = CssProps::new();
         let v = match rest { "start" => "flex-start", "end" => "flex-end", "center" => "center", "between" => "space-between", "around" => "space-around", "evenly" => "space-evenly", other => other };
-        p.insert("justify-content".into(), json!(v));
+        p.insert("justify-content".into(), json!(v).into());
         return Some(p);
     }
     if let Some(value) = class.strip_prefix("p-") {
-        return parse_tailwind_spacing(value, &|px| padding_props(&["padding"], px));
+        return parse_tailwind_spacing(value, vars, &|len| padding_props(&["padding"], len));
     }
     if let Some(value) = class.strip_prefix("px-") {
-        return parse_tailwind_spacing(value, &|px| padding_props(&["padding-left", "padding-right"], px));
+        return parse_tailwind_spacing(value, vars, &|len| padding_props(&["padding-left", "padding-right"], len));
     }
     if let Some(value) = class.strip_prefix("py-") {
-        return parse_tailwind_spacing(value, &|px| padding_props(&["padding-top", "padding-bottom"], px));
+        return parse_tailwind_spacing(value, vars, &|len| padding_props(&["padding-top", "padding-bottom"], len));
     }
-    for &(prefix, prop) in &[("pt-", "padding-top"), ("pr-", "padding-right"), ("pb-", "padding-bottom"), ("pl-", "padding-left")] {
+    for &(prefix, prop) in &[("pt-", "padding-top"), ("pr-", "padding-right"), ("pb-", "padding-bottom"), ("pl-", "padding-left"),
+        ("ps-", "padding-inline-start"), ("pe-", "padding-inline-end")] {
         if let Some(value) = class.strip_prefix(prefix) {
-            return parse_tailwind_spacing(value, &|px| padding_props(&[prop], px));
+            return parse_tailwind_spacing(value, vars, &|len| padding_props(&[prop], len));
         }
     }
     // Margin utilities
     if let Some(value) = class.strip_prefix("m-") {
         if value == "auto" {
             let mut p = CssProps::new();
-            p.insert("margin".into(), json!("auto"));
+            p.insert("margin".into(), json!("auto").into());
             return Some(p);
         }
-        return parse_tailwind_spacing(value, &|px| margin_props(&["margin"], px));
+        return parse_tailwind_spacing(value, vars, &|len| margin_props(&["margin"], len));
     }
     if let Some(value) = class.strip_prefix("mx-") {
         if value == "auto" {
             let mut p = CssProps::new();
-            p.insert("margin-left".into(), json!("auto"));
-            p.insert("margin-right".into(), json!("auto"));
+            p.insert("margin-left".into(), json!("auto").into());
+            p.insert("margin-right".into(), json!("auto").into());
             return Some(p);
         }
-        return parse_tailwind_spacing(value, &|px| margin_props(&["margin-left", "margin-right"], px));
+        return parse_tailwind_spacing(value, vars, &|len| margin_props(&["margin-left", "margin-right"], len));
     }
     if let Some(value) = class.strip_prefix("my-") {
         if value == "auto" {
             let mut p = CssProps::new();
-            p.insert("margin-top".into(), json!("auto"));
-            p.insert("margin-bottom".into(), json!("auto"));
+            p.insert("margin-top".into(), json!("auto").into());
+            p.insert("margin-bottom".into(), json!("auto").into());
             return Some(p);
         }
-        return parse_tailwind_spacing(value, &|px| margin_props(&["margin-top", "margin-bottom"], px));
+        return parse_tailwind_spacing(value, vars, &|len| margin_props(&["margin-top", "margin-bottom"], len));
     }
-    for &(prefix, prop) in &[("mt-", "margin-top"), ("mr-", "margin-right"), ("mb-", "margin-bottom"), ("ml-", "margin-left")] {
+    for &(prefix, prop) in &[("mt-", "margin-top"), ("mr-", "margin-right"), ("mb-", "margin-bottom"), ("ml-", "margin-left"),
+        ("ms-", "margin-inline-start"), ("me-", "margin-inline-end")] {
         if let Some(value) = class.strip_prefix(prefix) {
             if value == "auto" {
                 let mut p = CssProps::new();
-                p.insert(prop.into(), json!("auto"));
+                p.insert(prop.into(), json!("auto").into());
                 return Some(p);
             }
-            return parse_tailwind_spacing(value, &|px| margin_props(&[prop], px));
+            return parse_tailwind_spacing(value, vars, &|len| margin_props(&[prop], len));
         }
     }
     // Gap utilities (works in Android with Flexbox)
     if let Some(value) = class.strip_prefix("gap-") {
         if !value.starts_with("x-") && !value.starts_with("y-") {
-            return parse_tailwind_spacing(value, &|px| {
+            return parse_tailwind_spacing(value, vars, &|len| {
                 let mut props = CssProps::new();
-                props.insert("gap".into(), json!(format!("{}px", px)));
+                props.insert("gap".into(), json!(len).into());
                 props
             });
         }
     }
     if let Some(value) = class.strip_prefix("gap-x-") {
-        return parse_tailwind_spacing(value, &|px| {
+        return parse_tailwind_spacing(value, vars, &|len| {
             let mut props = CssProps::new();
-            props.insert("column-gap".into(), json!(format!("{}px", px)));
+            props.insert("column-gap".into(), json!(len).into());
             props
         });
     }
     if let Some(value) = class.strip_prefix("gap-y-") {
-        return parse_tailwind_spacing(value, &|px| {
-            let mut props = CssProps::new();
-            props.insert("row-gap".into(), json!(format!("{}px", px)));
-            props
-        });
-    }
-    // Space utilities (space-x-*, space-y-*)
-    if let Some(value) = class.strip_prefix("space-x-") {
-        return parse_tailwind_spacing(value, &|px| {
+        return parse_tailwind_spacing(value, vars, &|len| {
             let mut props = CssProps::new();
-            // In CSS, this is typically done with :not(:last-child) selector
-            // For now, we'll set it as a custom property that can be used
-            props.insert("--space-x".into(), json!(format!("{}px", px)));
-            props
-        });
-    }
-    if let Some(value) = class.strip_prefix("space-y-") {
-        return parse_tailwind_spacing(value, &|px| {
-            let mut props = CssProps::new();
-            props.insert("--space-y".into(), json!(format!("{}px", px)));
+            props.insert("row-gap".into(), json!(len).into());
             props
         });
     }
     // Font weight utilities
     match class {
-        "font-thin" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("100")); return Some(p); }
-        "font-extralight" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("200")); return Some(p); }
-        "font-light" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("300")); return Some(p); }
-        "font-normal" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("400")); return Some(p); }
-        "font-medium" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("500")); return Some(p); }
-        "font-semibold" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("600")); return Some(p); }
-        "font-bold" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("700")); return Some(p); }
-        "font-extrabold" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("800")); return Some(p); }
-        "font-black" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("900")); return Some(p); }
+        "font-thin" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("100").into()); return Some(p); }
+        "font-extralight" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("200").into()); return Some(p); }
+        "font-light" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("300").into()); return Some(p); }
+        "font-normal" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("400").into()); return Some(p); }
+        "font-medium" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("500").into()); return Some(p); }
+        "font-semibold" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("600").into()); return Some(p); }
+        "font-bold" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("700").into()); return Some(p); }
+        "font-extrabold" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("800").into()); return Some(p); }
+        "font-black" => { let mut p = CssProps::new(); p.insert("font-weight".into(), json!("900").into()); return Some(p); }
         _ => {}
     }
     // Font family utilities
     match class {
-        "font-sans" => { let mut p = CssProps::new(); p.insert("font-family".into(), json!("system-ui, -apple-system, sans-serif")); return Some(p); }
-        "font-serif" => { let mut p = CssProps::new(); p.insert("font-family".into(), json!("Georgia, serif")); return Some(p); }
-        "font-mono" => { let mut p = CssProps::new(); p.insert("font-family".into(), json!("ui-monospace, monospace")); return Some(p); }
+        "font-sans" => { let mut p = CssProps::new(); p.insert("font-family".into(), json!("system-ui, -apple-system, sans-serif").into()); return Some(p); }
+        "font-serif" => { let mut p = CssProps::new(); p.insert("font-family".into(), json!("Georgia, serif").into()); return Some(p); }
+        "font-mono" => { let mut p = CssProps::new(); p.insert("font-family".into(), json!("ui-monospace, monospace").into()); return Some(p); }
         _ => {}
     }
+    if let Some(token) = class.strip_prefix("font-").and_then(|name| theme_token(vars, "fontFamily", name)) {
+        let mut p = CssProps::new();
+        p.insert("font-family".into(), json!(token).into());
+        return Some(p);
+    }
     // Text size utilities
     match class {
-        "text-xs" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("12px")); p.insert("line-height".into(), json!("16px")); return Some(p); }
-        "text-sm" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("14px")); p.insert("line-height".into(), json!("20px")); return Some(p); }
-        "text-base" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("16px")); p.insert("line-height".into(), json!("24px")); return Some(p); }
-        "text-lg" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("18px")); p.insert("line-height".into(), json!("28px")); return Some(p); }
-        "text-xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("20px")); p.insert("line-height".into(), json!("28px")); return Some(p); }
-        "text-2xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("24px")); p.insert("line-height".into(), json!("32px")); return Some(p); }
-        "text-3xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("30px")); p.insert("line-height".into(), json!("36px")); return Some(p); }
-        "text-4xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("36px")); p.insert("line-height".into(), json!("40px")); return Some(p); }
-        "text-5xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("48px")); p.insert("line-height".into(), json!("1")); return Some(p); }
-        "text-6xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("60px")); p.insert("line-height".into(), json!("1")); return Some(p); }
+        "text-xs" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("12px").into()); p.insert("line-height".into(), json!("16px").into()); return Some(p); }
+        "text-sm" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("14px").into()); p.insert("line-height".into(), json!("20px").into()); return Some(p); }
+        "text-base" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("16px").into()); p.insert("line-height".into(), json!("24px").into()); return Some(p); }
+        "text-lg" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("18px").into()); p.insert("line-height".into(), json!("28px").into()); return Some(p); }
+        "text-xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("20px").into()); p.insert("line-height".into(), json!("28px").into()); return Some(p); }
+        "text-2xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("24px").into()); p.insert("line-height".into(), json!("32px").into()); return Some(p); }
+        "text-3xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("30px").into()); p.insert("line-height".into(), json!("36px").into()); return Some(p); }
+        "text-4xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("36px").into()); p.insert("line-height".into(), json!("40px").into()); return Some(p); }
+        "text-5xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("48px").into()); p.insert("line-height".into(), json!("1").into()); return Some(p); }
+        "text-6xl" => { let mut p = CssProps::new(); p.insert("font-size".into(), json!("60px").into()); p.insert("line-height".into(), json!("1").into()); return Some(p); }
         _ => {}
     }
+    if let Some(token) = class.strip_prefix("text-").and_then(|name| theme_token(vars, "fontSize", name)) {
+        let mut p = CssProps::new();
+        p.insert("font-size".into(), json!(token_length(&token)).into());
+        return Some(p);
+    }
     // Text alignment
     match class {
-        "text-left" => { let mut p = CssProps::new(); p.insert("text-align".into(), json!("left")); return Some(p); }
-        "text-center" => { let mut p = CssProps::new(); p.insert("text-align".into(), json!("center")); return Some(p); }
-        "text-right" => { let mut p = CssProps::new(); p.insert("text-align".into(), json!("right")); return Some(p); }
-        "text-justify" => { let mut p = CssProps::new(); p.insert("text-align".into(), json!("justify")); return Some(p); }
+        "text-left" => { let mut p = CssProps::new(); p.insert("text-align".into(), json!("left").into()); return Some(p); }
+        "text-center" => { let mut p = CssProps::new(); p.insert("text-align".into(), json!("center").into()); return Some(p); }
+        "text-right" => { let mut p = CssProps::new(); p.insert("text-align".into(), json!("right").into()); return Some(p); }
+        "text-justify" => { let mut p = CssProps::new(); p.insert("text-align".into(), json!("justify").into()); return Some(p); }
+        "text-start" => { let mut p = CssProps::new(); p.insert("text-align".into(), json!("start").into()); return Some(p); }
+        "text-end" => { let mut p = CssProps::new(); p.insert("text-align".into(), json!("end").into()); return Some(p); }
         _ => {}
     }
     // Overflow utilities
     match class {
-        "overflow-auto" => { let mut p = CssProps::new(); p.insert("overflow".into(), json!("auto")); return Some(p); }
-        "overflow-hidden" => { let mut p = CssProps::new(); p.insert("overflow".into(), json!("hidden")); return Some(p); }
-        "overflow-visible" => { let mut p = CssProps::new(); p.insert("overflow".into(), json!("visible")); return Some(p); }
-        "overflow-scroll" => { let mut p = CssProps::new(); p.insert("overflow".into(), json!("scroll")); return Some(p); }
-        "overflow-x-auto" => { let mut p = CssProps::new(); p.insert("overflow-x".into(), json!("auto")); return Some(p); }
-        "overflow-x-hidden" => { let mut p = CssProps::new(); p.insert("overflow-x".into(), json!("hidden")); return Some(p); }
-        "overflow-x-scroll" => { let mut p = CssProps::new(); p.insert("overflow-x".into(), json!("scroll")); return Some(p); }
-        "overflow-y-auto" => { let mut p = CssProps::new(); p.insert("overflow-y".into(), json!("auto")); return Some(p); }
-        "overflow-y-hidden" => { let mut p = CssProps::new(); p.insert("overflow-y".into(), json!("hidden")); return Some(p); }
-        "overflow-y-scroll" => { let mut p = CssProps::new(); p.insert("overflow-y".into(), json!("scroll")); return Some(p); }
+        "overflow-auto" => { let mut p = CssProps::new(); p.insert("overflow".into(), json!("auto").into()); return Some(p); }
+        "overflow-hidden" => { let mut p = CssProps::new(); p.insert("overflow".into(), json!("hidden").into()); return Some(p); }
+        "overflow-visible" => { let mut p = CssProps::new(); p.insert("overflow".into(), json!("visible").into()); return Some(p); }
+        "overflow-scroll" => { let mut p = CssProps::new(); p.insert("overflow".into(), json!("scroll").into()); return Some(p); }
+        "overflow-x-auto" => { let mut p = CssProps::new(); p.insert("overflow-x".into(), json!("auto").into()); return Some(p); }
+        "overflow-x-hidden" => { let mut p = CssProps::new(); p.insert("overflow-x".into(), json!("hidden").into()); return Some(p); }
+        "overflow-x-scroll" => { let mut p = CssProps::new(); p.insert("overflow-x".into(), json!("scroll").into()); return Some(p); }
+        "overflow-y-auto" => { let mut p = CssProps::new(); p.insert("overflow-y".into(), json!("auto").into()); return Some(p); }
+        "overflow-y-hidden" => { let mut p = CssProps::new(); p.insert("overflow-y".into(), json!("hidden").into()); return Some(p); }
+        "overflow-y-scroll" => { let mut p = CssProps::new(); p.insert("overflow-y".into(), json!("scroll").into()); return Some(p); }
         _ => {}
     }
     // Opacity utilities
     if let Some(value) = class.strip_prefix("opacity-") {
         if let Ok(opacity) = value.parse::<f32>() {
             let mut p = CssProps::new();
-            p.insert("opacity".into(), json!(opacity / 100.0));
+            p.insert("opacity".into(), json!(opacity / 100.0).into());
             return Some(p);
         }
     }
     // Shadow utilities (basic cross-platform support)
     match class {
-        "shadow-sm" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 1px 2px 0 rgba(0, 0, 0, 0.05)")); return Some(p); }
-        "shadow" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 1px 3px 0 rgba(0, 0, 0, 0.1), 0 1px 2px -1px rgba(0, 0, 0, 0.1)")); return Some(p); }
-        "shadow-md" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 4px 6px -1px rgba(0, 0, 0, 0.1), 0 2px 4px -2px rgba(0, 0, 0, 0.1)")); return Some(p); }
-        "shadow-lg" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 10px 15px -3px rgba(0, 0, 0, 0.1), 0 4px 6px -4px rgba(0, 0, 0, 0.1)")); return Some(p); }
-        "shadow-xl" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 20px 25px -5px rgba(0, 0, 0, 0.1), 0 8px 10px -6px rgba(0, 0, 0, 0.1)")); return Some(p); }
-        "shadow-2xl" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 25px 50px -12px rgba(0, 0, 0, 0.25)")); return Some(p); }
-        "shadow-none" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("none")); return Some(p); }
+        "shadow-sm" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 1px 2px 0 rgba(0, 0, 0, 0.05)").into()); return Some(p); }
+        "shadow" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 1px 3px 0 rgba(0, 0, 0, 0.1), 0 1px 2px -1px rgba(0, 0, 0, 0.1)").into()); return Some(p); }
+        "shadow-md" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 4px 6px -1px rgba(0, 0, 0, 0.1), 0 2px 4px -2px rgba(0, 0, 0, 0.1)").into()); return Some(p); }
+        "shadow-lg" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 10px 15px -3px rgba(0, 0, 0, 0.1), 0 4px 6px -4px rgba(0, 0, 0, 0.1)").into()); return Some(p); }
+        "shadow-xl" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 20px 25px -5px rgba(0, 0, 0, 0.1), 0 8px 10px -6px rgba(0, 0, 0, 0.1)").into()); return Some(p); }
+        "shadow-2xl" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("0 25px 50px -12px rgba(0, 0, 0, 0.25)").into()); return Some(p); }
+        "shadow-none" => { let mut p = CssProps::new(); p.insert("box-shadow".into(), json!("none").into()); return Some(p); }
+        _ => {}
+    }
+    if let Some(token) = class.strip_prefix("shadow-").and_then(|name| theme_token(vars, "shadow", name)) {
+        let mut p = CssProps::new();
+        p.insert("box-shadow".into(), json!(token).into());
+        return Some(p);
+    }
+    // Aspect ratio and replaced-content (image/video) fitting
+    match class {
+        "aspect-auto" => { let mut p = CssProps::new(); p.insert("aspect-ratio".into(), json!("auto").into()); return Some(p); }
+        "aspect-square" => { let mut p = CssProps::new(); p.insert("aspect-ratio".into(), json!("1 / 1").into()); return Some(p); }
+        "aspect-video" => { let mut p = CssProps::new(); p.insert("aspect-ratio".into(), json!("16 / 9").into()); return Some(p); }
         _ => {}
     }
+    if let Some(rest) = class.strip_prefix("object-") {
+        let (prop, value) = match rest {
+            "contain" | "cover" | "fill" | "none" | "scale-down" => ("object-fit", rest.to_string()),
+            "center" | "top" | "bottom" | "left" | "right" => ("object-position", rest.to_string()),
+            "left-top" | "left-bottom" | "right-top" | "right-bottom" => ("object-position", rest.replace('-', " ")),
+            _ => ("", String::new()),
+        };
+        if !prop.is_empty() {
+            let mut p = CssProps::new();
+            p.insert(prop.into(), json!(value).into());
+            return Some(p);
+        }
+    }
     // Parse arbitrary values like bg-[var(--primary)], text-[#ff0000], etc.
     if let Some(arb_value) = parse_arbitrary_value(class) {
         return Some(arb_value);
@@ -1635,29 +2070,21 @@ fn dynamic_css_properties_for_class(class: &str, vars: &IndexMap<String, String>
     if let Some(rest) = class.strip_prefix("text-") {
         if let Some(hex) = get_tailwind_color_with_vars(rest, vars) {
             let mut props = CssProps::new();
-            props.insert("color".into(), json!(hex));
+            props.insert("color".into(), json!(hex).into());
             return Some(props);
         }
     }
     // bg-{color}-{shade}
     if let Some(rest) = class.strip_prefix("bg-") {
         match rest {
-            "white" => { let mut p = CssProps::new(); p.insert("background-color".into(), json!("#ffffff")); return Some(p); }
-            "black" => { let mut p = CssProps::new(); p.insert("background-color".into(), json!("#000000")); return Some(p); }
-            "transparent" => { let mut p = CssProps::new(); p.insert("background-color".into(), json!("#00000000")); return Some(p); }
+            "white" => { let mut p = CssProps::new(); p.insert("background-color".into(), json!("#ffffff").into()); return Some(p); }
+            "black" => { let mut p = CssProps::new(); p.insert("background-color".into(), json!("#000000").into()); return Some(p); }
+            "transparent" => { let mut p = CssProps::new(); p.insert("background-color".into(), json!("#00000000").into()); return Some(p); }
             _ => {}
         }
         if let Some(hex) = get_tailwind_color_with_vars(rest, vars) {
             let mut props = CssProps::new();
-            props.insert("background-color".into(), json!(hex));
-            return Some(props);
-        }
-    }
-    // divide-{color}-{shade} (sets border-color for child dividers)
-    if let Some(rest) = class.strip_prefix("divide-") {
-        if let Some(hex) = get_tailwind_color_with_vars(rest, vars) {
-            let mut props = CssProps::new();
-            props.insert("border-color".into(), json!(hex));
+            props.insert("background-color".into(), json!(hex).into());
             return Some(props);
         }
     }
@@ -1674,8 +2101,8 @@ fn dynamic_css_properties_for_class(class: &str, vars: &IndexMap<String, String>
         
         let parts: Vec<&str> = rest.split('-').collect();
         
-        // Check if first part is a directional side (t, b, l, r, x, y)
-        let valid_sides = ["t", "b", "l", "r", "x", "y"];
+        // Check if first part is a directional side (t, b, l, r, x, y) or logical side (s, e)
+        let valid_sides = ["t", "b", "l", "r", "x", "y", "s", "e"];
         let (side, color_or_width_parts) = if parts.len() > 1 && valid_sides.contains(&parts[0]) {
             (Some(parts[0]), &parts[1..])
         } else {
@@ -1687,14 +2114,7 @@ fn dynamic_css_properties_for_class(class: &str, vars: &IndexMap<String, String>
             // Could be color-shade like "blue-500"
             let color_shade = color_or_width_parts.join("-");
             if let Some(hex) = get_tailwind_color_with_vars(&color_shade, vars) {
-                let mut props = CssProps::new();
-                let prop_name = if let Some(s) = side {
-                    format!("border-{}-color", s)
-                } else {
-                    "border-color".to_string()
-                };
-                props.insert(prop_name, json!(hex));
-                return Some(props);
+                return Some(border_color_props(side, &hex));
             }
         }
         
@@ -1702,14 +2122,7 @@ fn dynamic_css_properties_for_class(class: &str, vars: &IndexMap<String, String>
         if color_or_width_parts.len() == 1 {
             let potential_color = format!("{}-500", color_or_width_parts[0]);
             if let Some(hex) = get_tailwind_color_with_vars(&potential_color, vars) {
-                let mut props = CssProps::new();
-                let prop_name = if let Some(s) = side {
-                    format!("border-{}-color", s)
-                } else {
-                    "border-color".to_string()
-                };
-                props.insert(prop_name, json!(hex));
-                return Some(props);
+                return Some(border_color_props(side, &hex));
             }
         }
         
@@ -1720,17 +2133,19 @@ fn dynamic_css_properties_for_class(class: &str, vars: &IndexMap<String, String>
             }
         }
     }
-    // rounded* (border-radius)
-    if class == "rounded" { return Some(rounded_props(None, Some("md"))); }
-    if let Some(sz) = class.strip_prefix("rounded-") {
-        return Some(rounded_props(None, Some(sz)));
-    }
-    for &(pref, side) in &[("rounded-t", "t"), ("rounded-b", "b"), ("rounded-l", "l"), ("rounded-r", "r")] {
-        if class == pref { return Some(rounded_props(Some(side), Some("md"))); }
+    // rounded* (border-radius); sides first so `rounded-t-lg` isn't read as size `t-lg`
+    if class == "rounded" { return Some(rounded_props(None, Some("md"), vars)); }
+    for &(pref, side) in &[("rounded-t", "t"), ("rounded-b", "b"), ("rounded-l", "l"), ("rounded-r", "r"),
+        ("rounded-ss", "ss"), ("rounded-se", "se"), ("rounded-es", "es"), ("rounded-ee", "ee"),
+        ("rounded-s", "s"), ("rounded-e", "e")] {
+        if class == pref { return Some(rounded_props(Some(side), Some("md"), vars)); }
         if let Some(sz) = class.strip_prefix(&(pref.to_string() + "-")) {
-            return Some(rounded_props(Some(side), Some(sz)));
+            return Some(rounded_props(Some(side), Some(sz), vars));
         }
     }
+    if let Some(sz) = class.strip_prefix("rounded-") {
+        return Some(rounded_props(None, Some(sz), vars));
+    }
     // cursor-*
     if let Some(cur) = class.strip_prefix("cursor-") {
         let mut props = CssProps::new();
@@ -1742,25 +2157,24 @@ fn dynamic_css_properties_for_class(class: &str, vars: &IndexMap<String, String>
             "wait" => "wait",
             "not-allowed" => "not-allowed",
             other => other,
-        }));
+        }).into());
         return Some(props);
     }
     // transition*
     if class == "transition" || class == "transition-all" {
         let mut props = CssProps::new();
-        props.insert("transition-property".into(), json!("all"));
-        props.insert("transition-duration".into(), json!("150ms"));
-        props.insert("transition-timing-function".into(), json!("ease-in-out"));
+        props.insert("transition-property".into(), json!("all").into());
+        insert_transition_defaults(&mut props);
         return Some(props);
     }
     if class == "transition-none" {
         let mut props = CssProps::new();
-        props.insert("transition-property".into(), json!("none"));
-        props.insert("transition-duration".into(), json!("0ms"));
+        props.insert("transition-property".into(), json!("none").into());
+        props.insert("transition-duration".into(), json!("0ms").into());
         return Some(props);
     }
     if let Some(rest) = class.strip_prefix("transition-") {
-        // e.g., transition-colors → limit property; keep default duration/ease
+        // e.g., transition-colors → limit property; default duration/ease
         let mut props = CssProps::new();
         let property = match rest {
             "colors" => "color, background-color, border-color, fill, stroke",
@@ -1769,9 +2183,33 @@ fn dynamic_css_properties_for_class(class: &str, vars: &IndexMap<String, String>
             "shadow" => "box-shadow",
             other => other,
         };
-        props.insert("transition-property".into(), json!(property));
-        props.insert("transition-duration".into(), json!("150ms"));
-        props.insert("transition-timing-function".into(), json!("ease-in-out"));
+        props.insert("transition-property".into(), json!(property).into());
+        insert_transition_defaults(&mut props);
+        return Some(props);
+    }
+    // duration-*, delay-*, ease-*
+    if let Some(ms) = class.strip_prefix("duration-").and_then(|v| v.parse::<u32>().ok()) {
+        let mut props = CssProps::new();
+        props.insert("--tw-duration".into(), json!(format!("{}ms", ms)).into());
+        props.insert("transition-duration".into(), json!(format!("{}ms", ms)).into());
+        return Some(props);
+    }
+    if let Some(ms) = class.strip_prefix("delay-").and_then(|v| v.parse::<u32>().ok()) {
+        let mut props = CssProps::new();
+        props.insert("transition-delay".into(), json!(format!("{}ms", ms)).into());
+        return Some(props);
+    }
+    if let Some(ease) = class.strip_prefix("ease-") {
+        let timing = match ease {
+            "linear" => "linear",
+            "in" => "cubic-bezier(0.4, 0, 1, 1)",
+            "out" => "cubic-bezier(0, 0, 0.2, 1)",
+            "in-out" => "cubic-bezier(0.4, 0, 0.2, 1)",
+            _ => return None,
+        };
+        let mut props = CssProps::new();
+        props.insert("--tw-ease".into(), json!(timing).into());
+        props.insert("transition-timing-function".into(), json!(timing).into());
         return Some(props);
     }
     // width utilities: w-*, w-full, w-screen, w-min, w-max (treat min/max as auto), w-px
@@ -1794,34 +2232,38 @@ fn dynamic_css_properties_for_class(class: &str, vars: &IndexMap<String, String>
     if let Some(val) = class.strip_prefix("max-h-") {
         return width_like_props("max-height", val);
     }
+    // Logical insets follow the writing direction
+    if let Some(val) = class.strip_prefix("start-") {
+        return width_like_props("inset-inline-start", val);
+    }
+    if let Some(val) = class.strip_prefix("end-") {
+        return width_like_props("inset-inline-end", val);
+    }
     None
 }
 
-fn parse_tailwind_spacing<F>(value: &str, builder: &F) -> Option<CssProps>
+fn parse_tailwind_spacing<F>(value: &str, vars: &IndexMap<String, String>, builder: &F) -> Option<CssProps>
 where
-    F: Fn(i32) -> CssProps,
+    F: Fn(&str) -> CssProps,
 {
     if let Ok(n) = value.parse::<i32>() {
-        let px = n * 4;
-        return Some(builder(px));
+        return Some(builder(&format!("{}px", n * 4)));
     }
-    None
+    theme_token(vars, "spacing", value).map(|v| builder(&token_length(&v)))
 }
 
-fn padding_props(keys: &[&str], px_value: i32) -> CssProps {
+fn padding_props(keys: &[&str], value: &str) -> CssProps {
     let mut props = CssProps::new();
-    let val = format!("{}px", px_value);
     for key in keys {
-        props.insert((*key).into(), json!(&val));
+        props.insert((*key).into(), json!(value).into());
     }
     props
 }
 
-fn margin_props(keys: &[&str], px_value: i32) -> CssProps {
+fn margin_props(keys: &[&str], value: &str) -> CssProps {
     let mut props = CssProps::new();
-    let val = format!("{}px", px_value);
     for key in keys {
-        props.insert((*key).into(), json!(&val));
+        props.insert((*key).into(), json!(value).into());
     }
     props
 }
@@ -1831,39 +2273,67 @@ fn border_props(side: Option<&str>, width: i32, _vars: &IndexMap<String, String>
     let width_str = format!("{}px", width);
     match side {
         None => {
-            props.insert("border-width".into(), json!(&width_str));
+            props.insert("border-width".into(), json!(&width_str).into());
         }
         Some("t") => {
-            props.insert("border-top-width".into(), json!(&width_str));
+            props.insert("border-top-width".into(), json!(&width_str).into());
         }
         Some("b") => {
-            props.insert("border-bottom-width".into(), json!(&width_str));
+            props.insert("border-bottom-width".into(), json!(&width_str).into());
         }
         Some("l") => {
-            props.insert("border-left-width".into(), json!(&width_str));
+            props.insert("border-left-width".into(), json!(&width_str).into());
         }
         Some("r") => {
-            props.insert("border-right-width".into(), json!(&width_str));
+            props.insert("border-right-width".into(), json!(&width_str).into());
         }
         Some("x") => {
-            props.insert("border-left-width".into(), json!(&width_str));
-            props.insert("border-right-width".into(), json!(&width_str));
+            props.insert("border-left-width".into(), json!(&width_str).into());
+            props.insert("border-right-width".into(), json!(&width_str).into());
         }
         Some("y") => {
-            props.insert("border-top-width".into(), json!(&width_str));
-            props.insert("border-bottom-width".into(), json!(&width_str));
+            props.insert("border-top-width".into(), json!(&width_str).into());
+            props.insert("border-bottom-width".into(), json!(&width_str).into());
+        }
+        Some("s") => {
+            props.insert("border-inline-start-width".into(), json!(&width_str).into());
+        }
+        Some("e") => {
+            props.insert("border-inline-end-width".into(), json!(&width_str).into());
         }
         _ => {
-            props.insert("border-width".into(), json!(&width_str));
+            props.insert("border-width".into(), json!(&width_str).into());
         }
     };
-    props.insert("border-color".into(), json!("var(border)"));
-    props.insert("border-style".into(), json!("solid"));
+    props.insert("border-color".into(), json!("var(border)").into());
+    props.insert("border-style".into(), json!("solid").into());
     props
 }
 
-fn rounded_props(side: Option<&str>, size: Option<&str>) -> CssProps {
+/// `border-color` for a side; logical sides use `border-inline-*`
+fn border_color_props(side: Option<&str>, color: &str) -> CssProps {
+    let sides: &[&str] = match side {
+        Some("t") => &["top"],
+        Some("b") => &["bottom"],
+        Some("l") => &["left"],
+        Some("r") => &["right"],
+        Some("x") => &["left", "right"],
+        Some("y") => &["top", "bottom"],
+        Some("s") => &["inline-start"],
+        Some("e") => &["inline-end"],
+        _ => &[],
+    };
     let mut props = CssProps::new();
+    if sides.is_empty() {
+        props.insert("border-color".into(), json!(color).into());
+    }
+    for s in sides {
+        props.insert(format!("border-{}-color", s), json!(color).into());
+    }
+    props
+}
+
+fn rounded_props(side: Option<&str>, size: Option<&str>, vars: &IndexMap<String, String>) -> CssProps {
     let px = match size.unwrap_or("md") {
         "none" => 0,
         "sm" => 2,
@@ -1873,9 +2343,18 @@ fn rounded_props(side: Option<&str>, size: Option<&str>) -> CssProps {
         "2xl" => 16,
         "3xl" => 24,
         "full" => 9999,
-        s => s.parse::<i32>().unwrap_or(4),
+        s => match (s.parse::<i32>(), theme_token(vars, "radius", s)) {
+            (Ok(n), _) => n,
+            (Err(_), Some(token)) => return side_radius(side, json!(token_length(&token))),
+            (Err(_), None) => 4,
+        },
     };
-    let v = json!(format!("{}px", px));
+    side_radius(side, json!(format!("{}px", px)))
+}
+
+fn side_radius(side: Option<&str>, v: serde_json::Value) -> CssProps {
+    let v = PropValue::from(v);
+    let mut props = CssProps::new();
     match side {
         None => { props.insert("border-radius".into(), v); }
         Some("t") => {
@@ -1888,6 +2367,12 @@ fn rounded_props(side: Option<&str>, size: Option<&str>) -> CssProps {
         }
         Some("l") => { props.insert("border-top-left-radius".into(), v.clone()); props.insert("border-bottom-left-radius".into(), v); }
         Some("r") => { props.insert("border-top-right-radius".into(), v.clone()); props.insert("border-bottom-right-radius".into(), v); }
+        Some("s") => { props.insert("border-start-start-radius".into(), v.clone()); props.insert("border-end-start-radius".into(), v); }
+        Some("e") => { props.insert("border-start-end-radius".into(), v.clone()); props.insert("border-end-end-radius".into(), v); }
+        Some("ss") => { props.insert("border-start-start-radius".into(), v); }
+        Some("se") => { props.insert("border-start-end-radius".into(), v); }
+        Some("es") => { props.insert("border-end-start-radius".into(), v); }
+        Some("ee") => { props.insert("border-end-end-radius".into(), v); }
         _ => { props.insert("border-radius".into(), v); }
     }
     props
@@ -1917,10 +2402,82 @@ fn width_like_props(prop: &str, token: &str) -> Option<CssProps> {
             }
         }
     }?;
-    props.insert(prop.into(), json!(value));
+    props.insert(prop.into(), json!(value).into());
     Some(props)
 }
 
+/// Android gravity along the cross axis for an `align-items`/`align-self` value
+fn cross_axis_gravity(value: &str, is_horizontal: bool) -> &'static str {
+    match value {
+        "center" => if is_horizontal { "center_vertical" } else { "center_horizontal" },
+        "flex-start" | "start" => if is_horizontal { "top" } else { "start" },
+        "flex-end" | "end" => if is_horizontal { "bottom" } else { "end" },
+        "stretch" => if is_horizontal { "fill_vertical" } else { "fill_horizontal" },
+        _ => "",
+    }
+}
+
+/// Grow and shrink factors from `flex` (number, keyword or `<grow> <shrink> <basis>`) with
+/// `flexGrow`/`flexShrink` taking precedence
+fn flex_factors(styles: &IndexMap<String, serde_json::Value>) -> (Option<f64>, Option<f64>) {
+    let number = |v: &serde_json::Value| v.as_f64().or_else(|| v.as_str()?.trim().parse().ok());
+    let (mut grow, mut shrink) = match styles.get("flex") {
+        Some(v) if v.is_number() => (v.as_f64(), Some(1.0)),
+        Some(v) => match v.as_str().map(str::trim) {
+            Some("none") => (Some(0.0), Some(0.0)),
+            Some("auto") => (Some(1.0), Some(1.0)),
+            Some("initial") => (Some(0.0), Some(1.0)),
+            Some(s) => {
+                let mut parts = s.split_whitespace().map(|p| p.parse::<f64>().ok());
+                (parts.next().flatten(), parts.next().flatten().or(Some(1.0)))
+            }
+            None => (None, None),
+        },
+        None => (None, None),
+    };
+    if let Some(g) = styles.get("flexGrow").and_then(number) {
+        grow = Some(g);
+    }
+    if let Some(s) = styles.get("flexShrink").and_then(number) {
+        shrink = Some(s);
+    }
+    (grow, shrink)
+}
+
+/// `object-position` keywords or percentages as fractions of the free space (x, y)
+fn parse_object_position(value: &str) -> Option<(f32, f32)> {
+    let (mut x, mut y) = (None, None);
+    let mut free = Vec::new();
+    for token in value.split_whitespace() {
+        match token {
+            "left" => x = Some(0.0),
+            "right" => x = Some(1.0),
+            "top" => y = Some(0.0),
+            "bottom" => y = Some(1.0),
+            "center" => free.push(0.5),
+            t => free.push(t.strip_suffix('%')?.parse::<f32>().ok()? / 100.0),
+        }
+    }
+    // Unkeyworded values fill x first, then y
+    for v in free {
+        if x.is_none() { x = Some(v) } else if y.is_none() { y = Some(v) }
+    }
+    Some((x.unwrap_or(0.5), y.unwrap_or(0.5)))
+}
+
+/// `16 / 9`, `4/3` or a bare number as (width, height); `auto` has no ratio
+fn parse_aspect_ratio(value: &serde_json::Value) -> Option<(f32, f32)> {
+    if let Some(n) = value.as_f64() {
+        return (n > 0.0).then_some((n as f32, 1.0));
+    }
+    let s = value.as_str()?;
+    let (w, h) = match s.split_once('/') {
+        Some((w, h)) => (w.trim().parse::<f32>().ok()?, h.trim().parse::<f32>().ok()?),
+        None => (s.trim().parse::<f32>().ok()?, 1.0),
+    };
+    (w > 0.0 && h > 0.0).then_some((w, h))
+}
+
 fn trim_trailing_zeros(num: f64) -> String {
     let mut s = format!("{:.6}", num);
     while s.contains('.') && s.ends_with('0') { s.pop(); }
@@ -1932,17 +2489,6 @@ fn trim_trailing_zeros(num: f64) -> String {
 
 // static RE_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?P<prefix>(hover:)?(xs:|sm:|md:|lg:|xl:)*)?(?P<base>.+)$").unwrap());
 
-fn css_escape_class(class: &str) -> String { class.replace(':', "\\:") }
-
-fn class_to_selector(class: &str) -> String {
-    let (_bp, hover, base) = parse_prefixed_class(class);
-    if hover {
-        format!(".{}:hover", css_escape_class(&base))
-    } else {
-        format!(".{}", css_escape_class(&base))
-    }
-}
-
 // ------------- helpers for CSS output of media selectors -------------
 
 /// Flatten CSS with potential selectors that include media prelude.
@@ -1958,7 +2504,7 @@ pub fn post_process_css(
     let mut media_map: IndexMap<String, Vec<(String, CssProps)>> = IndexMap::new();
     for (sel, props) in raw_rules.iter() {
         if let Some((media, inner)) = sel.split_once('{') {
-            if media.trim_start().starts_with("@media ") && inner.ends_with('}') {
+            if media.trim_start().starts_with('@') && inner.ends_with('}') {
                 let inner_sel = inner.trim_end_matches('}').to_string();
                 media_map
                     .entry(media.trim().to_string())
@@ -1983,7 +2529,7 @@ pub fn post_process_css(
             out.push_str(&sel);
             out.push('{');
             out.push_str(&css_props_string(&props, vars));
-            out.push_str("}");
+            out.push('}');
         }
         out.push_str("}\n");
     }
@@ -1992,32 +2538,16 @@ pub fn post_process_css(
 
 // -------- Prefix parsing (hover:, breakpoint:) --------
 
-fn parse_prefixed_class(class: &str) -> (Option<String>, bool, String) {
-    // Split by ':' to find prefixes like md:hover:block
-    let parts: Vec<&str> = class.split(':').collect();
-    if parts.len() == 1 {
-        return (None, false, class.to_string());
-    }
-    let mut bp: Option<String> = None;
-    let mut hover = false;
-    for &p in &parts[..parts.len() - 1] {
-        match p {
-            "hover" => hover = true,
-            "xs" | "sm" | "md" | "lg" | "xl" => bp = Some(p.to_string()),
-            _ => {}
-        }
+fn wrap_with_media(selector: &str, bp_key: Option<&str>, motion: Option<&str>, bps: &IndexMap<String, String>) -> String {
+    let mut conditions = Vec::new();
+    if let Some(val) = bp_key.and_then(|k| bps.get(k)) {
+        conditions.push(format!("(min-width: {})", val));
     }
-    let base = parts.last().unwrap().to_string();
-    (bp, hover, base)
-}
-
-fn wrap_with_media(selector: &str, bp_key: Option<&str>, bps: &IndexMap<String, String>) -> String {
-    if let Some(k) = bp_key {
-        if let Some(val) = bps.get(k) {
-            return format!("@media (min-width: {}) {{{}}}", val, selector);
-        }
+    conditions.extend(motion.map(str::to_string));
+    if conditions.is_empty() {
+        return selector.to_string();
     }
-    selector.to_string()
+    format!("@media {} {{{}}}", conditions.join(" and "), selector)
 }
 
 /// Get a Tailwind color hex value from a string like "slate-200" or "blue-500"
@@ -2071,7 +2601,7 @@ fn get_tailwind_color_with_vars(color_shade: &str, vars: &IndexMap<String, Strin
     // Handle cases where the color name doesn't have a shade but we need to look for a variable
     // e.g., "primary" (from bg-primary) → look for "color.primary"
     let parts: Vec<&str> = color_shade.split('-').collect();
-    if parts.len() >= 1 {
+    if !parts.is_empty() {
         let color_name = parts[0];
         
         // Try direct variable
@@ -2088,38 +2618,53 @@ fn get_tailwind_color_with_vars(color_shade: &str, vars: &IndexMap<String, Strin
     None
 }
 
-/// Parse arbitrary values like bg-[var(--primary)], text-[#ff0000], border-[hsl(200,50%,50%)]
+/// Look up a named design token for a utility family, e.g. `rounded-card` → `radius.card`.
+///
+/// Namespaces: `spacing` (p/m/gap), `radius` (rounded), `fontSize` (text), `shadow`,
+/// `fontFamily` (font) and `zIndex` (z). `vars` are the effective theme variables, so tokens
+/// defined in a parent theme are found too.
+fn theme_token(vars: &IndexMap<String, String>, namespace: &str, name: &str) -> Option<String> {
+    vars.get(&format!("{}.{}", namespace, name)).cloned()
+}
+
+/// Bare numbers in length tokens are pixels, as in theme selectors
+fn token_length(value: &str) -> String {
+    match value.trim().parse::<f64>() {
+        Ok(n) => format!("{}px", trim_trailing_zeros(n)),
+        Err(_) => value.trim().to_string(),
+    }
+}
+
+/// Parse arbitrary values like bg-[var(--primary)], text-[#ff0000], border-[hsl(200,50%,50%)], aspect-[4/3]
+/// (divide-[...] is handled with the child utilities). The value picks the property, as in
+/// Tailwind: `text-[14px]` is a font size and `border-[3px]` a width; anything else is rejected.
 fn parse_arbitrary_value(class: &str) -> Option<CssProps> {
     // Match pattern: prefix-[value]
-    if let Some(bracket_start) = class.find('[') {
-        if !class.ends_with(']') {
-            return None;
-        }
-        let prefix = &class[..bracket_start];
-        let value = &class[bracket_start + 1..class.len() - 1];
-        
-        let mut props = CssProps::new();
-        match prefix {
-            "bg" => {
-                props.insert("background-color".into(), json!(value));
-                return Some(props);
-            }
-            "text" => {
-                props.insert("color".into(), json!(value));
-                return Some(props);
-            }
-            "border" => {
-                props.insert("border-color".into(), json!(value));
-                return Some(props);
-            }
-            "divide" => {
-                props.insert("border-color".into(), json!(value));
-                return Some(props);
-            }
-            _ => return None,
-        }
+    let bracket_start = class.find('[')?;
+    if !class.ends_with(']') {
+        return None;
     }
-    None
+    let prefix = class[..bracket_start].trim_end_matches('-');
+    let value = &class[bracket_start + 1..class.len() - 1];
+    let is_color = value.trim_start().starts_with("var(") || color::evaluate_color(value, None, &IndexMap::new()).is_some();
+    let is_length = matches!(value::CssValue::parse(value), value::CssValue::Length(n, _) if n >= 0.0) || value == "0";
+    let prop = match prefix {
+        "bg" if is_color => "background-color",
+        "bg" if value.starts_with("url(") || value.contains("gradient(") => "background-image",
+        "text" if is_color => "color",
+        "text" if is_length => "font-size",
+        "border" if is_color => "border-color",
+        "border" if is_length => "border-width",
+        "aspect" => {
+            let mut props = CssProps::new();
+            props.insert("aspect-ratio".into(), json!(value.replace('/', " / ")).into());
+            return Some(props);
+        }
+        _ => return None,
+    };
+    let mut props = CssProps::new();
+    props.insert(prop.into(), json!(value).into());
+    Some(props)
 }
 
 // re-export minimal API for CLI
@@ -2131,6 +2676,18 @@ pub mod api {
 mod tests {
     use super::*;
 
+    /// State with `theme` registered as the default and current theme "t", at `density` for
+    /// both display and font scaling
+    fn state_with(theme: ThemeEntry, density: f32) -> State {
+        let mut st = State { display_density: density, scaled_density: density, default_theme: "t".into(), current_theme: "t".into(), ..Default::default() };
+        st.themes.insert("t".into(), theme);
+        st
+    }
+
+    fn classes(list: &[&str]) -> Vec<String> {
+        list.iter().map(|c| c.to_string()).collect()
+    }
+
     #[test]
     fn default_theme_has_p2() {
         let mut st = State::new_default();
@@ -2145,8 +2702,8 @@ mod tests {
         let mut st = State::new_default();
         // Add a theme with button styles
         let mut styles = IndexMap::new();
-        let mut button_props = IndexMap::new();
-        button_props.insert("backgroundColor".to_string(), json!("#007bff"));
+        let mut button_props = CssProps::new();
+        button_props.insert("backgroundColor".to_string(), json!("#007bff").into());
         styles.insert("button".to_string(), button_props);
         st.add_theme("default", styles);
         st.set_theme("default").ok();
@@ -2206,7 +2763,7 @@ mod tests {
         let mut st = State::new_default();
         let mut selectors = SelectorStyles::new();
         let mut props = CssProps::new();
-        props.insert("color".to_string(), serde_json::json!("#ff0000"));
+        props.insert("color".to_string(), serde_json::json!("#ff0000").into());
         selectors.insert("h1, h2, h3".to_string(), props);
         
         st.add_theme("test", selectors);
@@ -2230,7 +2787,7 @@ mod tests {
         let mut st = State::new_default();
         let mut selectors = SelectorStyles::new();
         let mut props = CssProps::new();
-        props.insert("padding".to_string(), serde_json::json!("10px"));
+        props.insert("padding".to_string(), serde_json::json!("10px").into());
         selectors.insert(".btn, .link".to_string(), props);
         
         st.add_theme("test", selectors);
@@ -2299,6 +2856,1154 @@ mod tests {
         assert_eq!(android.get("display").and_then(|v| v.as_str()), Some("flex"));
     }
 
+    #[test]
+    fn space_and_divide_child_rules() {
+        let mut st = State::new_default();
+        st.register_tailwind_classes([
+            "space-x-4".to_string(),
+            "space-y-reverse".to_string(),
+            "divide-y-2".to_string(),
+            "divide-dashed".to_string(),
+            "divide-red-500".to_string(),
+        ]);
+        let css = st.css_for_web();
+        assert!(css.contains(".space-x-4 > * + *{"));
+        assert!(css.contains("margin-left:calc(16px * calc(1 - var(--tw-space-x-reverse)));"));
+        assert!(css.contains(".space-y-reverse > * + *{--tw-space-y-reverse:1;}"));
+        assert!(css.contains("border-top-width:calc(2px * calc(1 - var(--tw-divide-y-reverse)));"));
+        assert!(css.contains(".divide-dashed > * + *{border-style:dashed;}"));
+        assert!(css.contains(".divide-red-500 > * + *{border-color:#ef4444;}"));
+        assert!(!css.contains("--space-x"));
+    }
+
+    #[test]
+    fn android_child_spacing_and_divider_hints() {
+        let mut st = State::new_default();
+        st.display_density = 2.0;
+        let classes = ["space-y-2".to_string(), "divide-y".to_string(), "divide-[#112233]".to_string()];
+        let styles = st.android_styles_for("div", &classes);
+        assert_eq!(styles.get("androidChildSpacingVertical"), Some(&json!(16)));
+        assert_eq!(styles.get("androidDividerVertical"), Some(&json!(2)));
+        assert_eq!(styles.get("androidDividerColor").and_then(|v| v.as_str()), Some("#112233"));
+        // Children hints must not leak onto the parent's own box
+        assert!(!styles.contains_key("borderColor"));
+        assert!(!styles.contains_key("marginTop"));
+    }
+
+    #[test]
+    fn shadow_list_parsing() {
+        let units = units::UnitContext::new(2.0, 2.0, &Default::default());
+        let layers = shadow::parse_shadow_list("inset 0 2px 4px rgb(0 0 0 / 20%), 1px 10px 15px -3px #00000019", &units);
+        assert_eq!(layers.len(), 2);
+        assert!(layers[0].inset);
+        assert_eq!(layers[0].color.as_deref(), Some("rgb(0 0 0 / 20%)"));
+        assert_eq!((layers[1].offset_x, layers[1].offset_y, layers[1].blur, layers[1].spread), (1.0, 10.0, 15.0, -3.0));
+        assert!(shadow::parse_shadow_list("none", &units).is_empty());
+        assert!(shadow::parse_shadow_list("red", &units).is_empty());
+        // Any unit the unit engine knows resolves to dp
+        let layers = shadow::parse_shadow_list("0 0 1rem black, 0 3pt 6pt red", &units);
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0].blur, 16.0);
+        assert_eq!((layers[1].offset_y, layers[1].blur), (4.0, 8.0));
+    }
+
+    #[test]
+    fn android_box_and_text_shadow() {
+        let mut st = State::new_default();
+        let mut selectors = SelectorStyles::new();
+        let mut props = CssProps::new();
+        props.insert("textShadow".to_string(), json!("1px 2px 3px #ff0000").into());
+        selectors.insert(".title".to_string(), props);
+        st.add_theme("test", selectors);
+        st.set_theme("test").ok();
+        st.display_density = 2.0;
+
+        let styles = st.android_styles_for("div", &["shadow-lg".to_string(), "title".to_string()]);
+        // shadow-lg estimates to 8dp -> 16px at density 2
+        assert_eq!(styles.get("elevation"), Some(&json!(16)));
+        assert_eq!(styles.get("androidShadows").and_then(|v| v.as_array()).map(|a| a.len()), Some(2));
+        assert!(styles.contains_key("androidShadowSpotColor"));
+        assert!(styles.contains_key("androidShadowAmbientColor"));
+        let text = &styles.get("androidTextShadows").unwrap()[0];
+        assert_eq!(text["dx"], json!(2));
+        assert_eq!(text["dy"], json!(4));
+        assert_eq!(text["radius"], json!(6));
+        assert_eq!(text["color"], json!("#ff0000"));
+
+        let styles = st.android_styles_for("div", &["shadow-2xl".to_string()]);
+        assert_eq!(styles.get("elevation"), Some(&json!(48)));
+
+        // Shadow lengths follow the theme's px setting and the element's font size
+        st.themes.get_mut("test").unwrap().units.px = Some(units::PxUnit::Px);
+        let mut props = CssProps::new();
+        props.insert("fontSize".to_string(), json!("20px").into());
+        props.insert("textShadow".to_string(), json!("0.1em 4px 0 red").into());
+        st.themes.get_mut("test").unwrap().selectors.insert(".caption".to_string(), props);
+        let styles = st.android_styles_for("div", &["caption".to_string()]);
+        let text = &styles.get("androidTextShadows").unwrap()[0];
+        assert_eq!(text["dx"], json!(2)); // 0.1 * 10dp font
+        assert_eq!(text["dy"], json!(4)); // 4 physical px
+    }
+
+    #[test]
+    fn border_shorthand_parsing() {
+        let b = border::parse_border_shorthand("2px dashed rgb(0 0 0 / 20%)").unwrap();
+        assert_eq!((b.width.as_str(), b.style.as_str(), b.color.as_str()), ("2px", "dashed", "rgb(0 0 0 / 20%)"));
+        let b = border::parse_border_shorthand("thin solid red").unwrap();
+        assert_eq!((b.width.as_str(), b.color.as_str()), ("1px", "red"));
+        let b = border::parse_border_shorthand("solid").unwrap();
+        assert_eq!((b.width.as_str(), b.color.as_str()), ("3px", "currentColor"));
+        assert_eq!(border::parse_border_shorthand("none").unwrap().width, "3px");
+        assert_eq!(border::parse_border_shorthand("2px hidden").unwrap().width, "2px");
+        assert!(border::parse_border_shorthand("-1px solid").is_none());
+        assert!(border::parse_border_shorthand("1px 2px solid").is_none());
+        // `auto` is an outline style only
+        assert!(border::parse_border_shorthand("2px auto red").is_none());
+        assert_eq!(border::parse_outline_shorthand("2px auto red").unwrap().style, "auto");
+        assert!(border::expand_border_shorthand("outline", "auto").is_some());
+    }
+
+    #[test]
+    fn border_shorthand_web_and_android() {
+        let mut st = State::new_default();
+        let mut selectors = SelectorStyles::new();
+        let mut props = CssProps::new();
+        props.insert("border".to_string(), json!("1dp solid var(line)").into());
+        props.insert("borderTop".to_string(), json!("thick double #00ff00").into());
+        props.insert("outline".to_string(), json!("2px dotted blue").into());
+        selectors.insert(".card".to_string(), props);
+        st.add_theme("test", selectors);
+        st.set_theme("test").ok();
+        let mut vars = IndexMap::new();
+        vars.insert("line".to_string(), "#cccccc".to_string());
+        st.set_variables(vars);
+        st.display_density = 2.0;
+
+        st.register_tailwind_classes(["card".to_string()]);
+        let css = st.css_for_web();
+        assert!(css.contains("border-width:1px;border-style:solid;border-color:#cccccc;"));
+        assert!(css.contains("border-top-width:5px;border-top-style:double;border-top-color:#00ff00;"));
+        assert!(css.contains("outline-style:dotted;"));
+        assert!(!css.contains("border:"));
+
+        let styles = st.android_styles_for("div", &["card".to_string()]);
+        assert_eq!(styles.get("borderWidth"), Some(&json!(2)));
+        assert_eq!(styles.get("borderStyle").and_then(|v| v.as_str()), Some("solid"));
+        assert_eq!(styles.get("borderColor").and_then(|v| v.as_str()), Some("#cccccc"));
+        assert_eq!(styles.get("borderTopWidth"), Some(&json!(10)));
+        assert_eq!(styles.get("borderTopColor").and_then(|v| v.as_str()), Some("#00ff00"));
+        assert_eq!(styles.get("outlineWidth"), Some(&json!(4)));
+        assert_eq!(styles.get("outlineColor").and_then(|v| v.as_str()), Some("#0000ff"));
+        assert!(!styles.contains_key("border"));
+    }
+
+    #[test]
+    fn border_style_none_keeps_specified_width() {
+        let mut st = State::new_default();
+        let mut selectors = SelectorStyles::new();
+        let mut x = CssProps::new();
+        x.insert("border".to_string(), json!("2px none red").into());
+        selectors.insert(".x".to_string(), x);
+        let mut y = CssProps::new();
+        y.insert("borderStyle".to_string(), json!("solid").into());
+        selectors.insert(".y".to_string(), y);
+        st.add_theme("test", selectors);
+        st.set_theme("test").ok();
+        st.display_density = 2.0;
+
+        let styles = st.android_styles_for("div", &["x".to_string(), "y".to_string()]);
+        assert_eq!(styles.get("borderWidth"), Some(&json!(4)));
+        assert_eq!(styles.get("borderStyle").and_then(|v| v.as_str()), Some("solid"));
+        assert_eq!(styles.get("borderColor").and_then(|v| v.as_str()), Some("#ff0000"));
+    }
+
+    #[test]
+    fn css_color_parsing() {
+        let vars = IndexMap::new();
+        let hex = |v: &str| color::resolve_color(v, None, &vars);
+        assert_eq!(hex("rgb(255, 0, 0)"), "#ff0000");
+        assert_eq!(hex("rgba(0, 0, 0, 0.5)"), "#00000080");
+        assert_eq!(hex("rgb(0 0 0 / 20%)"), "#00000033");
+        assert_eq!(hex("rgb(100% 50% 0%)"), "#ff8000");
+        assert_eq!(hex("hsl(120, 100%, 25%)"), "#008000");
+        assert_eq!(hex("hsl(0.5turn 100% 50%)"), "#00ffff");
+        assert_eq!(hex("hwb(0 0% 0%)"), "#ff0000");
+        assert_eq!(hex("hwb(0 60% 60%)"), "#808080");
+        assert_eq!(hex("lab(100 0 0)"), "#ffffff");
+        assert_eq!(hex("lch(0% 0 0)"), "#000000");
+        assert_eq!(hex("oklab(1 0 0)"), "#ffffff");
+        assert_eq!(hex("oklch(62.8% 0.2577 29.23)"), "#ff0000");
+        assert_eq!(hex("color(srgb 0 0.5 1)"), "#0080ff");
+        assert_eq!(hex("REBECCAPURPLE"), "#663399");
+        assert_eq!(hex("aliceblue"), "#f0f8ff");
+        assert_eq!(hex("lightgoldenrodyellow"), "#fafad2");
+        assert_eq!(hex("yellowgreen"), "#9acd32");
+        assert_eq!(hex("green"), "#008000");
+        assert_eq!(hex("transparent"), "#00000000");
+        assert_eq!(hex("#ABC"), "#aabbcc");
+        assert_eq!(hex("currentColor"), "#000000");
+        assert_eq!(color::resolve_color("currentcolor", Some("navy"), &vars), "#000080");
+        assert_eq!(hex("var(--unknown)"), "var(--unknown)");
+        assert_eq!(hex("color-mix(in srgb, rgb(255 0 0), blue)"), "#800080");
+        assert_eq!(hex("color-mix(in srgb, rgb(255, 0, 0) 25%, blue)"), "#4000bf");
+        // Malformed hex is not a color; non-ASCII must not panic on byte slicing
+        for bad in ["#aé", "#éa", "#ééé", "#+ff", "#12345é"] {
+            assert_eq!(color::Color::parse(bad), None, "{}", bad);
+        }
+        assert_eq!(hex("#aé"), "#aé");
+    }
+
+    #[test]
+    fn contrast_audit_per_theme() {
+        let mut light = ThemeEntry::default();
+        light.variables.insert("text".into(), "#111827".into());
+        light.variables.insert("bg".into(), "#ffffff".into());
+        light.variables.insert("muted".into(), "#9ca3af".into());
+        light.contrast_pairs.insert("text".into(), "bg".into());
+        light.contrast_pairs.insert("muted".into(), "bg".into());
+        let mut input = CssProps::new();
+        input.insert("background-color".to_string(), json!("var(bg)").into());
+        input.insert("color".to_string(), json!("var(text)").into());
+        input.insert("placeholder-color".to_string(), json!("alpha(currentColor, 0.3)").into());
+        light.selectors.insert("input".into(), input);
+        let mut heading = CssProps::new();
+        heading.insert("color".to_string(), json!("#767676").into());
+        heading.insert("backgroundColor".to_string(), json!("white").into());
+        heading.insert("fontSize".to_string(), json!("24px").into());
+        light.selectors.insert("h1".into(), heading.clone());
+        // 18pt is 24px; viewport units can't be judged, so the text counts as normal
+        heading.insert("fontSize".to_string(), json!("18pt").into());
+        light.selectors.insert("h2".into(), heading.clone());
+        heading.insert("fontSize".to_string(), json!("2vw").into());
+        light.selectors.insert("h3".into(), heading);
+
+        // Dark theme only swaps variables; selectors and pairs are inherited
+        let mut dark = ThemeEntry { inherits: Some("light".into()), ..Default::default() };
+        dark.variables.insert("text".into(), "#1f2937".into());
+        dark.variables.insert("bg".into(), "#111827".into());
+
+        let mut state = State { default_theme: "light".into(), current_theme: "light".into(), ..Default::default() };
+        state.themes.insert("light".into(), light);
+        state.themes.insert("dark".into(), dark);
+
+        let reports = state.contrast_audit();
+        assert_eq!(reports.len(), 2);
+        let light = &reports[0];
+        assert_eq!(light.theme, "light");
+        assert_eq!(light.checks.len(), 7);
+        let text = &light.checks[0];
+        assert_eq!(text.source, contrast::PairSource::Variables { foreground: "text".into(), background: "bg".into() });
+        assert_eq!(text.ratio, 17.74);
+        assert!(text.aa && text.aaa);
+        let muted = &light.checks[1];
+        assert!(!muted.aa && !muted.aaa);
+        let placeholder = &light.checks[3];
+        assert_eq!(placeholder.source, contrast::PairSource::Selector { selector: "input".into(), property: "placeholder-color".into() });
+        assert_eq!(placeholder.foreground, "#b8babe");
+        assert!(!placeholder.aa);
+        // 4.54:1 gray on white passes AA for normal text; at 24px it also passes AAA-large
+        let h1 = &light.checks[4];
+        assert!(h1.large_text && h1.aa && h1.aaa);
+        assert!(light.checks[5].large_text && light.checks[5].aaa);
+        assert!(!light.checks[6].large_text && !light.checks[6].aaa);
+
+        let dark = &reports[1];
+        assert_eq!(dark.theme, "dark");
+        let failing: Vec<_> = dark.aa_failures().iter().map(|c| c.source.clone()).collect();
+        assert!(failing.contains(&contrast::PairSource::Variables { foreground: "text".into(), background: "bg".into() }));
+        assert!(failing.contains(&contrast::PairSource::Selector { selector: "input".into(), property: "color".into() }));
+        assert!(dark.aa_failures().len() > light.aa_failures().len());
+    }
+
+    #[test]
+    fn derive_dark_theme_inverts_lightness() {
+        let mut light = ThemeEntry { name: Some("Light".into()), ..Default::default() };
+        light.variables.insert("bg".into(), "#ffffff".into());
+        light.variables.insert("text".into(), "#000000".into());
+        light.variables.insert("primary".into(), "#3b82f6".into());
+        light.variables.insert("onPrimary".into(), "#ffffff".into());
+        light.variables.insert("surface".into(), "var(bg)".into());
+        light.variables.insert("gap".into(), "16px".into());
+        light.contrast_pairs.insert("text".into(), "bg".into());
+        light.contrast_pairs.insert("onPrimary".into(), "primary".into());
+
+        let dark = light.derive_dark("light");
+        assert_eq!(dark.name.as_deref(), Some("Light Dark"));
+        assert_eq!(dark.inherits.as_deref(), Some("light"));
+        assert!(dark.selectors.is_empty());
+        assert_eq!(dark.variables.get("bg").map(String::as_str), Some("#000000"));
+        assert_eq!(dark.variables.get("text").map(String::as_str), Some("#ffffff"));
+        // References and non-colors are inherited unchanged
+        assert!(!dark.variables.contains_key("surface"));
+        assert!(!dark.variables.contains_key("gap"));
+
+        // Hue is preserved while lightness flips
+        let hue = |hex: &str| color::Color::parse(hex).unwrap().to_space(color::ColorSpace::Oklch);
+        let (before, after) = (hue("#3b82f6"), hue(&dark.variables["primary"]));
+        assert!((before[2] - after[2]).abs() < 3.0);
+        assert!((before[0] + after[0] - 1.0).abs() < 0.02);
+
+        // Declared pairs meet WCAG AA in the generated theme
+        let mut state = State { default_theme: "light".into(), current_theme: "light".into(), ..Default::default() };
+        state.themes.insert("light".into(), light);
+        let derived = state.derive_dark_theme("light").unwrap();
+        state.themes.insert("dark".into(), derived);
+        let report = state.contrast_audit().into_iter().find(|r| r.theme == "dark").unwrap();
+        assert_eq!(report.checks.len(), 2);
+        assert!(report.aa_failures().is_empty());
+        assert!(state.derive_dark_theme("missing").is_none());
+    }
+
+    #[test]
+    fn derive_dark_theme_resolves_referenced_pairs() {
+        let mut light = ThemeEntry::default();
+        light.variables.insert("colors.bg".into(), "#ffffff".into());
+        light.variables.insert("colors.surface".into(), "var(colors.bg)".into());
+        light.variables.insert("colors.text".into(), "#777777".into());
+        light.variables.insert("colors.muted".into(), "var(colors.text)".into());
+        light.contrast_pairs.insert("colors.text".into(), "colors.surface".into());
+        light.contrast_pairs.insert("colors.muted".into(), "colors.surface".into());
+
+        let mut state = State { default_theme: "light".into(), current_theme: "light".into(), ..Default::default() };
+        state.themes.insert("light".into(), light);
+        let dark = state.derive_dark_theme("light").unwrap();
+        assert_eq!(dark.variables.get("colors.bg").map(String::as_str), Some("#000000"));
+        assert!(!dark.variables.contains_key("colors.surface"));
+        // Already adjusted through its target, so the reference is kept
+        assert!(!dark.variables.contains_key("colors.muted"));
+        let text = color::Color::parse(&dark.variables["colors.text"]).unwrap();
+        assert!(color::contrast_ratio(text, color::Color::BLACK) >= contrast::AA_NORMAL);
+
+        state.themes.insert("dark".into(), dark);
+        let report = state.contrast_audit().into_iter().find(|r| r.theme == "dark").unwrap();
+        assert!(report.aa_failures().is_empty());
+    }
+
+    #[test]
+    fn android_colors_use_argb_order() {
+        let mut theme = ThemeEntry::default();
+        let mut overlay = CssProps::new();
+        overlay.insert("backgroundColor".to_string(), json!("rgba(0, 0, 0, 0.5)").into());
+        overlay.insert("borderColor".to_string(), json!("#ff000080").into());
+        overlay.insert("boxShadow".to_string(), json!("0 2px 4px rgb(0 0 0 / 25%)").into());
+        overlay.insert("fontFamily".to_string(), json!("abc").into());
+        theme.selectors.insert(".overlay".into(), overlay);
+        let mut st = State { default_theme: "t".into(), current_theme: "t".into(), ..Default::default() };
+        st.themes.insert("t".into(), theme);
+
+        let input = st.android_styles_for("input", &[]);
+        assert_eq!(input.get("placeholderColor").and_then(|v| v.as_str()), Some("#70888888"));
+        assert_eq!(input.get("color").and_then(|v| v.as_str()), Some("#000000"));
+
+        let styles = st.android_styles_for("div", &["overlay".to_string()]);
+        assert_eq!(styles.get("backgroundColor").and_then(|v| v.as_str()), Some("#80000000"));
+        assert_eq!(styles.get("borderColor").and_then(|v| v.as_str()), Some("#80ff0000"));
+        assert_eq!(styles.get("androidShadowSpotColor").and_then(|v| v.as_str()), Some("#40000000"));
+        assert_eq!(styles["androidShadows"][0]["color"], json!("#40000000"));
+        // Non-color properties are never reinterpreted
+        assert_eq!(styles.get("fontFamily").and_then(|v| v.as_str()), Some("abc"));
+    }
+
+    #[test]
+    fn gradient_utilities_web_and_android() {
+        let mut theme = ThemeEntry::default();
+        theme.variables.insert("brand".into(), "#ff0000".into());
+        let mut hero = CssProps::new();
+        hero.insert("backgroundImage".to_string(), json!("linear-gradient(45deg, var(brand), rgba(0,0,255,0.5) 30%, white)").into());
+        theme.selectors.insert(".hero".into(), hero);
+        let mut st = State { default_theme: "t".into(), current_theme: "t".into(), ..Default::default() };
+        st.themes.insert("t".into(), theme);
+        for c in ["bg-gradient-to-r", "from-brand", "via-blue-500", "via-40%", "to-transparent"] {
+            st.used_classes.insert(c.to_string());
+        }
+
+        let css = st.css_for_web();
+        assert!(css.contains(".bg-gradient-to-r{background-image:linear-gradient(to right, var(--tw-gradient-stops));}"), "{}", css);
+        assert!(css.contains(".from-brand{--tw-gradient-from:#ff0000;--tw-gradient-from-fallback:#ff000000;--tw-gradient-stops:"), "{}", css);
+        assert!(css.contains("--tw-gradient-via-stop:#3b82f6 var(--tw-gradient-via-position, ),"), "{}", css);
+        assert!(css.contains(".via-40\\%{--tw-gradient-via-position:40%;}"), "{}", css);
+        assert!(css.contains(".to-transparent{--tw-gradient-to:transparent;"), "{}", css);
+
+        let classes = classes(&["bg-gradient-to-r", "from-brand", "via-blue-500", "via-40%"]);
+        let styles = st.android_styles_for("div", &classes);
+        assert_eq!(styles["androidGradient"], json!({
+            "type": "linear",
+            "angle": 90.0,
+            "orientation": "LEFT_RIGHT",
+            "colors": ["#ff0000", "#3b82f6", "#003b82f6"],
+            "positions": [0.0, 0.4, 1.0],
+        }));
+        assert!(!styles.keys().any(|k| k.starts_with("--")));
+
+        let styles = st.android_styles_for("div", &["hero".to_string()]);
+        let gradient = &styles["androidGradient"];
+        assert_eq!(gradient["orientation"], json!("BL_TR"));
+        assert_eq!(gradient["colors"], json!(["#ff0000", "#800000ff", "#ffffff"]));
+        assert_eq!(gradient["positions"], json!([0.0, 0.3, 1.0]));
+    }
+
+    #[test]
+    fn android_unit_engine() {
+        let mut theme = ThemeEntry::default();
+        theme.units.rem_base = Some(10.0);
+        let mut card = CssProps::new();
+        card.insert("fontSize".to_string(), json!("2rem").into());
+        card.insert("padding".to_string(), json!("1.5rem").into());
+        card.insert("marginTop".to_string(), json!("0.5em").into());
+        card.insert("letterSpacing".to_string(), json!("0.1em").into());
+        card.insert("lineHeight".to_string(), json!("1.5").into());
+        card.insert("width".to_string(), json!("50vw").into());
+        card.insert("height".to_string(), json!("10vh").into());
+        card.insert("borderWidth".to_string(), json!("3px").into());
+        card.insert("minHeight".to_string(), json!("50%").into());
+        theme.selectors.insert(".card".into(), card);
+        let mut label = CssProps::new();
+        label.insert("fontSize".to_string(), json!("32sp").into());
+        label.insert("marginLeft".to_string(), json!("4sp").into());
+        theme.selectors.insert(".label".into(), label);
+        let mut st = State { display_density: 2.0, scaled_density: 3.0, default_theme: "t".into(), current_theme: "t".into(), ..Default::default() };
+        st.themes.insert("t".into(), theme);
+
+        let styles = st.android_styles_for("div", &["card".to_string()]);
+        assert_eq!(styles["fontSize"], json!(60.0)); // 20dp at font scale 1.5
+        assert_eq!(styles["paddingTop"], json!(30)); // 15dp
+        assert_eq!(styles["marginTop"], json!(20)); // 0.5 * 20dp font
+        assert_eq!(styles["letterSpacing"], json!(4));
+        assert_eq!(styles["lineHeight"], json!(90.0)); // 1.5 * 20dp, scaled
+        assert_eq!(styles["borderWidth"], json!(6));
+        // Percentages of the parent are left for the host; viewport units need a screen size
+        assert_eq!(styles["minHeight"], json!("50%"));
+        assert_eq!(styles["width"], json!("50vw"));
+
+        st.set_viewport(400.0, 800.0);
+        let styles = st.android_styles_for("div", &["card".to_string()]);
+        assert_eq!(styles["width"], json!(400)); // 200dp
+        assert_eq!(styles["height"], json!(160)); // 80dp
+
+        let styles = st.android_styles_for("div", &["label".to_string()]);
+        assert_eq!(styles["fontSize"], json!(96.0));
+        assert_eq!(styles["marginLeft"], json!(12));
+
+        // Theme switch: px means physical pixels
+        st.themes.get_mut("t").unwrap().units.px = Some(units::PxUnit::Px);
+        let styles = st.android_styles_for("div", &["card".to_string()]);
+        assert_eq!(styles["borderWidth"], json!(3));
+        assert_eq!(styles["paddingTop"], json!(30));
+    }
+
+    #[test]
+    fn box_shorthand_expansion() {
+        let expand = |p: &str, v: &str| shorthands::expand_box_shorthand(p, v).unwrap().into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+        assert_eq!(expand("padding", "8dp"), ["8dp", "8dp", "8dp", "8dp"]);
+        assert_eq!(expand("padding", "8dp 16dp"), ["8dp", "16dp", "8dp", "16dp"]);
+        assert_eq!(expand("margin", "1px 2px 3px"), ["1px", "2px", "3px", "2px"]);
+        assert_eq!(expand("border-color", "red green blue rgb(0, 0, 0)"), ["red", "green", "blue", "rgb(0, 0, 0)"]);
+        assert_eq!(expand("border-radius", "10px 20px / 5px"), ["10px 5px", "20px 5px", "10px 5px", "20px 5px"]);
+        assert_eq!(expand("border-radius", "4px/4px"), ["4px", "4px", "4px", "4px"]);
+        assert!(shorthands::expand_box_shorthand("padding", "1px 2px 3px 4px 5px").is_none());
+        assert!(shorthands::expand_box_shorthand("gap", "1px 2px").is_none());
+
+        let mut theme = ThemeEntry::default();
+        theme.variables.insert("pad".into(), "8dp 16dp".into());
+        let mut card = CssProps::new();
+        card.insert("padding".to_string(), json!("var(pad)").into());
+        card.insert("margin".to_string(), json!("1px 2px 3px 4px").into());
+        card.insert("inset".to_string(), json!("0 auto").into());
+        card.insert("borderWidth".to_string(), json!("1px 0").into());
+        card.insert("borderColor".to_string(), json!("red blue").into());
+        card.insert("borderRadius".to_string(), json!("8px 4px / 2px").into());
+        card.insert("willChange".to_string(), json!("transform").into());
+        theme.selectors.insert(".card".into(), card);
+        let mut st = State { display_density: 2.0, default_theme: "t".into(), current_theme: "t".into(), ..Default::default() };
+        st.themes.insert("t".into(), theme);
+
+        // input's default "padding-vertical"/"padding-horizontal" are replaced by the per-side values
+        let styles = st.android_styles_for("input", &["card".to_string()]);
+        assert_eq!(styles["paddingTop"], json!(16));
+        assert_eq!(styles["paddingRight"], json!(32));
+        assert!(!styles.contains_key("padding") && !styles.contains_key("paddingVertical") && !styles.contains_key("paddingHorizontal"));
+        assert_eq!((&styles["marginTop"], &styles["marginLeft"]), (&json!(2), &json!(8)));
+        assert_eq!((&styles["top"], &styles["right"]), (&json!(0), &json!("auto")));
+        assert_eq!((&styles["borderTopWidth"], &styles["borderRightWidth"]), (&json!(2), &json!(0)));
+        assert!(!styles.contains_key("borderWidth"));
+        assert_eq!((&styles["borderTopColor"], &styles["borderLeftColor"]), (&json!("#ff0000"), &json!("#0000ff")));
+        assert_eq!(styles["borderTopLeftRadius"], json!(16));
+        assert_eq!(styles["androidCornerRadii"], json!([16, 4, 8, 4, 16, 4, 8, 4]));
+        assert!(!styles.contains_key("willChange"), "web-only properties stay out of native output");
+
+        let mut raw = IndexMap::new();
+        raw.insert("padding".to_string(), json!("4px 8px"));
+        raw.insert("paddingLeft".to_string(), json!(1));
+        raw.insert("borderRadius".to_string(), json!(6));
+        let processed = st.process_styles(raw);
+        assert_eq!(processed["paddingTop"], json!(8));
+        assert_eq!(processed["paddingRight"], json!(16));
+        assert_eq!(processed["paddingLeft"], json!(2));
+        assert!(!processed.contains_key("padding"));
+        assert_eq!(processed["borderBottomRightRadius"], json!(12));
+
+        let mut raw = IndexMap::new();
+        raw.insert("padding-top".to_string(), json!(3));
+        raw.insert("paddingTop".to_string(), json!(5));
+        raw.insert("will-change".to_string(), json!("transform"));
+        let processed = st.process_styles(raw);
+        assert_eq!(processed["paddingTop"], json!(10));
+        assert!(!processed.contains_key("padding-top") && !processed.contains_key("willChange"));
+
+        st.used_classes.insert("card".into());
+        assert!(st.css_for_web().contains("padding:8px 16px;"));
+    }
+
+    #[test]
+    fn native_math_functions() {
+        let mut ctx = units::UnitContext::new(2.0, 2.0, &Default::default());
+        assert_eq!(ctx.eval_math("calc(8px * 2)"), Ok(16.0));
+        assert_eq!(ctx.eval_math("calc(1rem + 4px)"), Ok(20.0));
+        assert_eq!(ctx.eval_math("calc((10px + 2px) / 4 - -1px)"), Ok(4.0));
+        assert_eq!(ctx.eval_math("min(10px, 2rem, calc(5px * 3))"), Ok(10.0));
+        assert_eq!(ctx.eval_math("max(1em, 20px)"), Ok(20.0));
+        assert_eq!(ctx.eval_math("clamp(20px, 5px, 18px)"), Ok(20.0));
+        assert!(ctx.eval_math("calc(100% - 8px)").unwrap_err().contains("parent size"));
+        assert!(ctx.eval_math("clamp(14px, 2vw, 18px)").unwrap_err().contains("viewport"));
+        assert!(ctx.eval_math("calc(4px + 2)").is_err());
+        assert!(ctx.eval_math("calc(4px * 2px)").is_err());
+        assert!(ctx.eval_math("calc(4px / 0)").is_err());
+        ctx.viewport_width = Some(800.0);
+        ctx.viewport_height = Some(600.0);
+        assert_eq!(ctx.eval_math("clamp(14px, 2vw, 18px)"), Ok(16.0));
+
+        let mut theme = ThemeEntry::default();
+        theme.variables.insert("spacing".into(), "6px".into());
+        let mut card = CssProps::new();
+        card.insert("marginTop".to_string(), json!("calc(var(spacing) * 2)").into());
+        card.insert("fontSize".to_string(), json!("clamp(14px, 2vw, 18px)").into());
+        card.insert("width".to_string(), json!("calc(100% - 16px)").into());
+        theme.selectors.insert(".card".into(), card);
+        let mut st = State { display_density: 2.0, scaled_density: 2.0, default_theme: "t".into(), current_theme: "t".into(), ..Default::default() };
+        st.themes.insert("t".into(), theme);
+
+        let styles = st.android_styles_for("div", &["card".to_string()]);
+        assert_eq!(styles["marginTop"], json!(24));
+        assert_eq!(styles["width"], json!("calc(100% - 16px)"));
+        let unresolved = styles["androidUnresolved"].as_array().unwrap();
+        let props: Vec<&str> = unresolved.iter().map(|u| u["property"].as_str().unwrap()).collect();
+        assert_eq!(props, ["width", "fontSize"]);
+
+        st.set_viewport(800.0, 600.0);
+        let styles = st.android_styles_for("div", &["card".to_string()]);
+        assert_eq!(styles["fontSize"], json!(32.0));
+        assert_eq!(styles["androidUnresolved"].as_array().map(|a| a.len()), Some(1));
+    }
+
+    #[test]
+    fn theme_tokens_per_utility_family() {
+        let mut base = ThemeEntry::default();
+        for (k, v) in [
+            ("radius.card", "12"),
+            ("spacing.gutter", "20px"),
+            ("fontSize.title", "1.5rem"),
+            ("shadow.card", "0 2px 4px rgba(0,0,0,0.2)"),
+            ("fontFamily.brand", "Inter, sans-serif"),
+            ("zIndex.modal", "50"),
+        ] {
+            base.variables.insert(k.into(), v.into());
+        }
+        let child = ThemeEntry { inherits: Some("base".into()), ..Default::default() };
+        let mut st = State { display_density: 1.0, scaled_density: 1.0, default_theme: "base".into(), current_theme: "child".into(), ..Default::default() };
+        st.themes.insert("base".into(), base);
+        st.themes.insert("child".into(), child);
+
+        let list = ["rounded-card", "rounded-t-card", "p-gutter", "mx-gutter", "gap-gutter", "text-title", "shadow-card", "font-brand", "z-modal"];
+        st.register_tailwind_classes(classes(&list));
+        let css = st.css_for_web();
+        assert!(css.contains(".rounded-card{border-radius:12px;}"));
+        assert!(css.contains(".rounded-t-card{border-top-left-radius:12px;border-top-right-radius:12px;}"));
+        assert!(css.contains(".p-gutter{padding:20px;}"));
+        assert!(css.contains(".mx-gutter{margin-left:20px;margin-right:20px;}"));
+        assert!(css.contains(".gap-gutter{gap:20px;}"));
+        assert!(css.contains(".text-title{font-size:1.5rem;}"));
+        assert!(css.contains(".shadow-card{box-shadow:0 2px 4px rgba(0,0,0,0.2);}"));
+        assert!(css.contains(".font-brand{font-family:Inter, sans-serif;}"));
+        // Built-in scale names still win, unknown names are not tokens
+        st.register_tailwind_classes(["rounded-lg".to_string(), "p-unknown".to_string()]);
+        let css = st.css_for_web();
+        assert!(css.contains(".rounded-lg{border-radius:8px;}"));
+        assert!(!css.contains(".p-unknown{padding"));
+
+        let styles = st.android_styles_for("span", &classes(&list));
+        assert_eq!(styles["paddingLeft"], json!(20));
+        assert_eq!(styles["marginLeft"], json!(20));
+        assert_eq!(styles["fontSize"], json!(24.0));
+        assert_eq!(styles["fontFamily"], json!("Inter, sans-serif"));
+        assert!(styles.contains_key("androidShadows"));
+        let styles = st.android_styles_for("div", &["z-modal".to_string()]);
+        assert_eq!(styles["elevation"], json!(50));
+    }
+
+    #[test]
+    fn keyframe_animations_web_and_android() {
+        let mut theme = ThemeEntry::default();
+        let mut wiggle = animation::Keyframes::new();
+        let mut at = |sel: &str, v: &str| {
+            let mut p = CssProps::new();
+            p.insert("transform".into(), json!(v).into());
+            wiggle.insert(sel.into(), p);
+        };
+        at("0%, 100%", "rotate(-3deg)");
+        at("50%", "rotate(3deg)");
+        theme.keyframes.insert("wiggle".into(), wiggle);
+        theme.variables.insert("animation.wiggle".into(), "wiggle 200ms ease-in-out infinite".into());
+        let mut flash = animation::Keyframes::new();
+        flash.insert("from".into(), [("backgroundColor".to_string(), json!("var(colors.brand)").into())].into_iter().collect());
+        flash.insert("to".into(), [("backgroundColor".to_string(), json!("#00ff0080").into())].into_iter().collect());
+        theme.keyframes.insert("flash".into(), flash);
+        theme.variables.insert("colors.brand".into(), "#3366ff".into());
+        let mut st = state_with(theme, 1.0);
+
+        st.register_tailwind_classes(["animate-spin".to_string(), "animate-wiggle".to_string(), "motion-reduce:animate-none".to_string()]);
+        let css = st.css_for_web();
+        assert!(css.contains(".animate-spin{animation:spin 1s linear infinite;}"));
+        assert!(css.contains(".animate-wiggle{animation:wiggle 200ms ease-in-out infinite;}"));
+        assert!(css.contains("@keyframes spin{from{transform:rotate(0deg);}to{transform:rotate(360deg);}}"));
+        assert!(css.contains("@keyframes wiggle{0%, 100%{transform:rotate(-3deg);}50%{transform:rotate(3deg);}}"));
+        assert!(css.contains("@media (prefers-reduced-motion: reduce){.motion-reduce\\:animate-none{animation:none;}}"));
+        assert!(!css.contains("@keyframes pulse"));
+
+        let classes = ["animate-bounce".to_string(), "motion-reduce:animate-none".to_string()];
+        let styles = st.android_styles_for("div", &classes);
+        let anim = &styles["androidAnimation"];
+        assert_eq!(anim["name"], json!("bounce"));
+        assert_eq!(anim["duration"], json!(1000.0));
+        assert_eq!(anim["iterations"], json!(-1.0));
+        let track = &anim["tracks"][0];
+        assert_eq!(track["property"], json!("translationY"));
+        let offsets: Vec<f64> = track["keyframes"].as_array().unwrap().iter().map(|k| k["offset"].as_f64().unwrap()).collect();
+        assert_eq!(offsets, [0.0, 0.5, 1.0]);
+        assert_eq!(track["keyframes"][0]["value"], json!("-25%"));
+        assert_eq!(track["keyframes"][1]["value"], json!(0.0));
+        assert_eq!(track["keyframes"][1]["easing"], json!("cubic-bezier(0, 0, 0.2, 1)"));
+
+        let ping = st.android_styles_for("div", &["animate-ping".to_string()]);
+        let tracks: Vec<&str> = ping["androidAnimation"]["tracks"].as_array().unwrap().iter().map(|t| t["property"].as_str().unwrap()).collect();
+        assert_eq!(tracks, ["scaleX", "scaleY", "alpha"]);
+        assert_eq!(ping["androidAnimation"]["tracks"][2]["keyframes"][0], json!({ "offset": 0.0, "value": 1.0 }));
+
+        let flash = st.android_styles_for("div", &["animate-flash".to_string()]);
+        let track = &flash["androidAnimation"]["tracks"][0];
+        assert_eq!(track["property"], json!("backgroundColor"));
+        assert_eq!(track["keyframes"][0]["value"], json!("#3366ff"));
+        assert_eq!(track["keyframes"][1]["value"], json!("#8000ff00"));
+
+        st.set_reduced_motion(true);
+        let styles = st.android_styles_for("div", &classes);
+        assert_eq!(styles["animation"], json!("none"));
+        assert!(!styles.contains_key("androidAnimation"));
+    }
+
+    #[test]
+    fn transition_utilities_on_android() {
+        let mut st = State::new_default();
+        st.register_tailwind_classes(["duration-300".to_string(), "delay-75".to_string(), "ease-out".to_string()]);
+        let css = st.css_for_web();
+        assert!(css.contains(".duration-300{--tw-duration:300ms;transition-duration:300ms;}"));
+        assert!(css.contains(".delay-75{transition-delay:75ms;}"));
+        assert!(css.contains(".ease-out{--tw-ease:cubic-bezier(0, 0, 0.2, 1);transition-timing-function:cubic-bezier(0, 0, 0.2, 1);}"));
+
+        let classes = classes(&["transition-colors", "duration-300", "delay-75", "ease-out"]);
+        let styles = st.android_styles_for("div", &classes);
+        assert_eq!(
+            styles["androidTransition"],
+            json!({
+                "properties": ["textColor", "backgroundColor", "strokeColor", "tint"],
+                "duration": 300.0,
+                "delay": 75.0,
+                "interpolator": "linear_out_slow_in",
+            })
+        );
+        assert!(!styles.keys().any(|k| k.starts_with("transition")));
+
+        // Theme shorthand, custom curves and transition-none
+        let mut theme = ThemeEntry::default();
+        let mut fade = CssProps::new();
+        fade.insert("transition".into(), json!("opacity 0.2s cubic-bezier(0.2, 0.8, 0.2, 1), transform 0.2s").into());
+        theme.selectors.insert(".fade".into(), fade);
+        st.themes.insert("t".into(), theme);
+        st.set_theme("t").unwrap();
+        let styles = st.android_styles_for("div", &["fade".to_string()]);
+        let transition = &styles["androidTransition"];
+        assert_eq!(transition["properties"], json!(["alpha", "rotation", "scaleX", "scaleY", "translationX", "translationY"]));
+        assert_eq!(transition["duration"], json!(200.0));
+        assert_eq!(transition["interpolator"], json!({ "type": "path", "controlPoints": [0.2, 0.8, 0.2, 1.0] }));
+        let styles = st.android_styles_for("div", &["transition-none".to_string()]);
+        assert!(!styles.contains_key("androidTransition"));
+    }
+
+    #[test]
+    fn explicit_transition_utilities_win_in_any_order() {
+        let mut st = State::new_default();
+        let classes = classes(&["duration-300", "ease-in", "delay-150", "transition-colors"]);
+        st.register_tailwind_classes(classes.clone());
+        let css = st.css_for_web();
+        assert!(css.contains("transition-duration:var(--tw-duration, 150ms);"), "{}", css);
+        assert!(css.contains("transition-timing-function:var(--tw-ease, ease-in-out);"), "{}", css);
+
+        let styles = st.android_styles_for("div", &classes);
+        let transition = &styles["androidTransition"];
+        assert_eq!(transition["duration"], json!(300.0));
+        assert_eq!(transition["delay"], json!(150.0));
+        assert_eq!(transition["interpolator"], json!("fast_out_linear_in"));
+        assert!(!styles.keys().any(|k| k.starts_with("--")));
+
+        // Without explicit utilities the defaults apply
+        let styles = st.android_styles_for("div", &["transition".to_string()]);
+        assert_eq!(styles["androidTransition"]["duration"], json!(150.0));
+        assert_eq!(styles["androidTransition"]["interpolator"], json!("accelerate_decelerate"));
+    }
+
+    #[test]
+    fn font_registry_web_and_android() {
+        let mut theme: ThemeEntry = serde_json::from_value(json!({
+            "fonts": {
+                "brand": {
+                    "family": "Inter",
+                    "fallbacks": ["system-ui", "sans-serif"],
+                    "faces": [
+                        { "weight": 400, "src": ["/fonts/Inter.woff2", "/fonts/Inter.ttf"], "android": "@font/inter_regular" },
+                        { "weight": 700, "src": ["/fonts/Inter \"Bold\".woff2"], "android": "@font/inter_bold" },
+                        { "weight": 400, "style": "italic", "android": "fonts/Inter-Italic.ttf" }
+                    ]
+                },
+                "display": { "family": "Lobster", "faces": [{ "src": ["/fonts/Lobster.woff2"] }] }
+            }
+        }))
+        .unwrap();
+        let mut italic = CssProps::new();
+        italic.insert("fontStyle".into(), json!("italic").into());
+        theme.selectors.insert(".italic".into(), italic);
+        // The registry takes precedence over a `fontFamily.brand` token
+        theme.variables.insert("fontFamily.brand".into(), "Arial".into());
+        let mut st = state_with(theme, 1.0);
+
+        st.register_tailwind_classes(["font-brand".to_string(), "font-mono".to_string()]);
+        let css = st.css_for_web();
+        assert!(css.starts_with("@font-face{font-family:\"Inter\";src:url(\"/fonts/Inter.woff2\") format(\"woff2\"), url(\"/fonts/Inter.ttf\") format(\"truetype\");font-weight:400;font-style:normal;font-display:swap;}"));
+        assert!(css.contains(r#"url("/fonts/Inter \"Bold\".woff2") format("woff2");font-weight:700;"#), "{}", css);
+        assert!(css.contains(".font-brand{font-family:\"Inter\", system-ui, sans-serif;}"));
+        assert!(!css.contains("Lobster"), "unused families get no @font-face");
+
+        let typeface = |list: &[&str]| st.android_styles_for("span", &classes(list)).get("androidTypeface").cloned().unwrap_or_default();
+        let bold = typeface(&["font-brand", "font-bold"]);
+        assert_eq!(bold["kind"], json!("resource"));
+        assert_eq!(bold["source"], json!("inter_bold"));
+        assert_eq!(bold["synthetic"], json!(false));
+        assert_eq!(bold["fallbacks"], json!(["sans-serif"]));
+        let semibold = typeface(&["font-brand", "font-semibold"]);
+        assert_eq!((semibold["source"].clone(), semibold["synthetic"].clone()), (json!("inter_bold"), json!(true)));
+        // No web source for Lobster on Android: nothing to map to
+        assert!(typeface(&["font-display"]).is_null());
+        assert_eq!(typeface(&["font-mono"]), json!({ "family": "monospace", "kind": "system", "weight": 400, "style": "normal" }));
+        let it = typeface(&["font-brand", "italic"]);
+        assert_eq!((it["kind"].clone(), it["source"].clone()), (json!("asset"), json!("fonts/Inter-Italic.ttf")));
+    }
+
+    #[test]
+    fn aspect_ratio_and_object_utilities() {
+        let mut st = State::new_default();
+        st.register_tailwind_classes(classes(&["aspect-video", "aspect-[4/3]", "object-cover", "object-left-top", "w-1/2"]));
+        let css = st.css_for_web();
+        assert!(css.contains(".aspect-video{aspect-ratio:16 / 9;}"));
+        assert!(css.contains(".aspect-\\[4\\/3\\]{aspect-ratio:4 / 3;}"));
+        assert!(css.contains(".object-cover{object-fit:cover;}"));
+        assert!(css.contains(".object-left-top{object-position:left top;}"));
+        assert!(css.contains(".w-1\\/2{width:50%;}"));
+
+        let android = |list: &[&str]| st.android_styles_for("img", &classes(list));
+        let styles = android(&["aspect-video", "object-cover", "object-top"]);
+        assert_eq!(styles["androidAspectRatio"], json!({ "ratio": 1.7778, "dimensionRatio": "16:9" }));
+        assert_eq!(styles["androidScaleType"], json!("center_crop"));
+        assert_eq!(styles["androidObjectPosition"], json!({ "x": 0.5, "y": 0.0 }));
+        let styles = android(&["aspect-[4/3]", "object-contain", "object-right-bottom"]);
+        assert_eq!(styles["androidAspectRatio"]["dimensionRatio"], json!("4:3"));
+        assert_eq!(styles["androidScaleType"], json!("fit_end"));
+        let styles = android(&["aspect-square", "object-contain", "object-left"]);
+        assert_eq!(styles["androidAspectRatio"]["ratio"], json!(1.0));
+        assert_eq!(styles["androidScaleType"], json!("fit_start"));
+        assert!(!android(&["aspect-auto"]).contains_key("androidAspectRatio"));
+    }
+
+    #[test]
+    fn arbitrary_values_pick_a_valid_property() {
+        let props = |class: &str| parse_arbitrary_value(class).map(|p| p.into_iter().map(|(k, v)| (k, v.into())).collect::<Vec<(String, serde_json::Value)>>());
+        assert_eq!(props("text-[#ff0000]"), Some(vec![("color".to_string(), json!("#ff0000"))]));
+        assert_eq!(props("text-[14px]"), Some(vec![("font-size".to_string(), json!("14px"))]));
+        assert_eq!(props("border-[3px]"), Some(vec![("border-width".to_string(), json!("3px"))]));
+        assert_eq!(props("border-[hsl(200,50%,50%)]"), Some(vec![("border-color".to_string(), json!("hsl(200,50%,50%)"))]));
+        assert_eq!(props("bg-[var(--primary)]"), Some(vec![("background-color".to_string(), json!("var(--primary)"))]));
+        assert_eq!(props("bg-[url(x.png)]"), Some(vec![("background-image".to_string(), json!("url(x.png)"))]));
+        // Values that fit none of the prefix's properties are not utilities
+        for class in ["bg-[14px]", "text-[bogus]", "border-[-2px]", "text-[50%]"] {
+            assert_eq!(props(class), None, "{}", class);
+        }
+    }
+
+    #[test]
+    fn flex_item_utilities() {
+        let mut st = State::new_default();
+        let list = ["flex-auto", "flex-none", "grow", "shrink-0", "basis-1/3", "order-last", "self-center", "content-between", "place-items-center"];
+        st.register_tailwind_classes(classes(&list));
+        let css = st.css_for_web();
+        assert!(css.contains(".flex-auto{flex:1 1 auto;}"));
+        assert!(css.contains(".flex-none{flex:none;}"));
+        assert!(css.contains(".grow{flex-grow:1;}"));
+        assert!(css.contains(".shrink-0{flex-shrink:0;}"));
+        assert!(css.contains(".basis-1\\/3{flex-basis:33.333333%;}"));
+        assert!(css.contains(".order-last{order:9999;}"));
+        assert!(css.contains(".self-center{align-self:center;}"));
+        assert!(css.contains(".content-between{align-content:space-between;}"));
+        assert!(css.contains(".place-items-center{align-items:center;justify-items:center;}"));
+        assert!(dynamic_css_properties_for_class("justify-items-normal", &IndexMap::new()).is_some());
+        assert!(dynamic_css_properties_for_class("justify-self-normal", &IndexMap::new()).is_none());
+        assert!(dynamic_css_properties_for_class("justify-items-bogus", &IndexMap::new()).is_none());
+
+        let android = |list: &[&str]| st.android_styles_for("div", &classes(list));
+        let styles = android(&["flex-auto", "shrink-0", "order-2", "self-end", "basis-8"]);
+        assert_eq!(styles["androidLayoutWeight"], json!(1.0));
+        assert_eq!(styles["androidFlexShrink"], json!(0.0));
+        assert_eq!(styles["androidOrder"], json!(2));
+        assert_eq!(styles["androidSelfGravity"], json!({ "horizontal": "bottom", "vertical": "end" }));
+        assert_eq!(styles["flexBasis"], json!(32));
+        let styles = android(&["flex-none"]);
+        assert_eq!((styles["androidLayoutWeight"].clone(), styles["androidFlexShrink"].clone()), (json!(0.0), json!(0.0)));
+        let styles = android(&["flex", "flex-wrap", "content-center", "place-items-center"]);
+        assert_eq!(styles["androidAlignContent"], json!("center"));
+        assert_eq!(styles["androidGravity"], json!("center_vertical"));
+    }
+
+    #[test]
+    fn visibility_and_interactivity_utilities() {
+        let mut st = State::new_default();
+        st.register_tailwind_classes(classes(&["invisible", "sr-only", "pointer-events-none", "select-none", "touch-pan-y"]));
+        let css = st.css_for_web();
+        assert!(css.contains(".invisible{visibility:hidden;}"));
+        assert!(css.contains(".sr-only{position:absolute;width:1px;height:1px;padding:0;margin:-1px;overflow:hidden;clip:rect(0, 0, 0, 0);white-space:nowrap;border-width:0;}"));
+        assert!(css.contains(".pointer-events-none{pointer-events:none;}"));
+        assert!(css.contains(".select-none{user-select:none;}"));
+        assert!(css.contains(".touch-pan-y{touch-action:pan-y;}"));
+
+        let android = |list: &[&str]| st.android_styles_for("span", &classes(list));
+        assert_eq!(android(&["hidden"])["androidVisibility"], json!("gone"));
+        assert_eq!(android(&["invisible"])["androidVisibility"], json!("invisible"));
+        assert_eq!(android(&["hidden", "visible"])["androidVisibility"], json!("gone"));
+        assert_eq!(android(&["sr-only"])["androidAccessibilityOnly"], json!(true));
+        assert!(!android(&["sr-only", "not-sr-only"]).contains_key("androidAccessibilityOnly"));
+        let styles = android(&["pointer-events-none", "select-text", "touch-none"]);
+        assert_eq!(styles["androidClickable"], json!(false));
+        assert_eq!(styles["androidFocusable"], json!(false));
+        assert_eq!(styles["androidTextSelectable"], json!(true));
+        assert_eq!(styles["androidTouchAction"], json!("none"));
+        assert_eq!(android(&["select-none"])["androidTextSelectable"], json!(false));
+    }
+
+    #[test]
+    fn accessibility_touch_targets_and_font_scale() {
+        let base: ThemeEntry = serde_json::from_value(json!({
+            "accessibility": { "min_touch_target": 48, "min_font_scale": 0.9, "max_font_scale": 1.3 },
+            "selectors": { ".icon-btn": { "width": "24px", "height": "48px", "cursor": "pointer" } }
+        }))
+        .unwrap();
+        let child = ThemeEntry { inherits: Some("base".into()), ..Default::default() };
+        let mut st = State { display_density: 2.0, scaled_density: 4.0, default_theme: "base".into(), current_theme: "child".into(), ..Default::default() };
+        st.themes.insert("base".into(), base);
+        st.themes.insert("child".into(), child);
+
+        let text = st.android_styles_for("span", &["text-base".to_string()]);
+        assert!((text["fontSize"].as_f64().unwrap() - 41.6).abs() < 0.01, "font scale 2.0 is capped at 1.3");
+        assert!(!text.contains_key("minHeight"), "plain text isn't tappable");
+        st.scaled_density = 1.0;
+        let text = st.android_styles_for("span", &["text-base".to_string()]);
+        assert!((text["fontSize"].as_f64().unwrap() - 28.8).abs() < 0.01, "font scale 0.5 is raised to 0.9");
+
+        let button = st.android_styles_for("button", &[]);
+        assert_eq!(button["minWidth"], json!(96));
+        assert_eq!(button["minHeight"], json!(96));
+        let icon = st.android_styles_for("div", &["icon-btn".to_string()]);
+        assert_eq!(icon["androidHitSlop"], json!({ "horizontal": 24 }));
+        assert_eq!(icon["width"], json!(48));
+        let disabled = st.android_styles_for("button", &["pointer-events-none".to_string()]);
+        assert!(!disabled.contains_key("minHeight"));
+
+        // Without a policy nothing changes
+        st.themes.get_mut("base").unwrap().accessibility = Default::default();
+        assert!(!st.android_styles_for("button", &[]).contains_key("minHeight"));
+    }
+
+    #[test]
+    fn logical_utilities_and_direction_variants() {
+        let mut st = state_with(ThemeEntry::default(), 2.0);
+        let classes = classes(&["ps-4", "me-2", "start-0", "text-start", "rounded-s-lg", "border-e-2", "rtl:ms-auto", "ltr:pe-1"]);
+        st.register_tailwind_classes(classes.clone());
+
+        let css = st.css_for_web();
+        assert!(css.contains(".ps-4{padding-inline-start:16px;}"), "{}", css);
+        assert!(css.contains("margin-inline-end:8px"));
+        assert!(css.contains("inset-inline-start:0px"));
+        assert!(css.contains("text-align:start"));
+        assert!(css.contains("border-start-start-radius:8px;border-end-start-radius:8px"));
+        assert!(css.contains("border-inline-end-width:2px"));
+        assert!(css.contains(r#".rtl\:ms-auto:where([dir="rtl"], [dir="rtl"] *){margin-inline-start:auto;}"#), "{}", css);
+
+        let ltr = st.android_styles_for("div", &classes);
+        assert_eq!(ltr["paddingStart"], json!(32));
+        assert_eq!(ltr["marginEnd"], json!(16));
+        assert_eq!(ltr["start"], json!(0));
+        assert_eq!(ltr["borderTopStartRadius"], json!(16));
+        assert_eq!(ltr["borderBottomStartRadius"], json!(16));
+        assert_eq!(ltr["borderEndWidth"], json!(4));
+        assert_eq!(ltr["paddingEnd"], json!(8), "ltr: applies in LTR layouts");
+        assert!(!ltr.contains_key("marginStart"));
+        assert!(!ltr.contains_key("paddingInlineStart") && !ltr.contains_key("paddingLeft"));
+
+        st.set_layout_direction(direction::LayoutDirection::Rtl);
+        let rtl = st.android_styles_for("div", &classes);
+        assert_eq!(rtl["paddingStart"], json!(32), "start stays start; Android mirrors it");
+        assert_eq!(rtl["marginStart"], json!("auto"));
+        assert!(!rtl.contains_key("paddingEnd"));
+        let physical = st.android_styles_for("p", &["text-left".to_string()]);
+        assert_eq!(physical["androidTextGravity"], json!("left"));
+    }
+
+    #[test]
+    fn property_spellings_collapse_when_merging() {
+        use property::{CssProperty, Platform};
+        assert_eq!(CssProperty::parse("backgroundColor"), CssProperty::parse("background-color"));
+        assert_eq!(CssProperty::parse("WebkitLineClamp").id(), "-webkit-line-clamp");
+        assert_eq!(CssProperty::parse("-ms-transform").camel(), "msTransform");
+        assert_eq!(CssProperty::parse("--brandColor").id(), "--brandColor");
+        assert_eq!(CssProperty::parse("paddingTop").shorthand().map(|d| d.id), Some("padding"));
+        assert!(!CssProperty::parse("willChange").supported_on(Platform::Native));
+        assert!(CssProperty::parse("fontSize").supported_on(Platform::Native));
+        assert!(!CssProperty::parse("paddingHorizontal").supported_on(Platform::Web));
+        assert!(!CssProperty::parse("android-gravity").supported_on(Platform::Web));
+
+        let parent: ThemeEntry = serde_json::from_value(json!({
+            "selectors": {
+                "div": { "background-color": "#111111", "padding-top": "4px" },
+                ".a": { "backgroundColor": "#222222" },
+                ".b": { "background-color": "#333333" }
+            }
+        }))
+        .unwrap();
+        let child: ThemeEntry = serde_json::from_value(json!({
+            "inherits": "parent",
+            "selectors": { "div": { "backgroundColor": "#444444" } }
+        }))
+        .unwrap();
+        let mut st = State { current_theme: "child".into(), display_density: 1.0, scaled_density: 1.0, ..Default::default() };
+        st.themes.insert("parent".into(), parent);
+        st.themes.insert("child".into(), child);
+
+        let (eff, _) = st.effective_theme_all();
+        assert_eq!(eff["div"].len(), 2, "{:?}", eff["div"]);
+        assert_eq!(eff["div"]["background-color"], json!("#444444"));
+
+        let classes = classes(&["a", "b", "p-2"]);
+        let styles = st.android_styles_for("div", &classes);
+        assert_eq!(styles["backgroundColor"], json!("#333333"), "the last class wins whatever its spelling");
+        assert_eq!(styles["paddingTop"], json!(8), "padding resets the theme's padding-top");
+
+        // Native aggregates don't outlive a later longhand or shorthand
+        let mut props = CssProps::new();
+        property::merge_property(&mut props, "paddingHorizontal", json!("4px").into());
+        property::merge_property(&mut props, "padding", json!("2px").into());
+        assert_eq!(props.keys().collect::<Vec<_>>(), ["padding"]);
+        let styles = st.android_styles_for("span", &["p-2".to_string(), "px-4".to_string()]);
+        assert!(!styles.contains_key("paddingHorizontal") && !styles.contains_key("padding"), "{:?}", styles);
+        assert_eq!((styles["paddingLeft"].clone(), styles["paddingTop"].clone()), (json!(16), json!(8)));
+        assert_eq!(styles["paddingVertical"], json!(8));
+        let styles = st.android_styles_for("span", &["m-2".to_string(), "mt-1".to_string()]);
+        assert!(!styles.contains_key("marginVertical") && styles.contains_key("marginHorizontal"));
+    }
+
+    #[test]
+    fn parsed_values_serialize_per_platform() {
+        use value::{CssValue, LengthUnit, Separator};
+        assert_eq!(CssValue::parse("18sp"), CssValue::Length(18.0, LengthUnit::Sp));
+        assert_eq!(CssValue::parse("50%"), CssValue::Percentage(50.0));
+        assert_eq!(CssValue::parse("150ms"), CssValue::Dimension(150.0, "ms".into()));
+        assert!(matches!(CssValue::parse("#ff0000"), CssValue::Color(..)));
+        assert_eq!(
+            CssValue::parse("4dp 8dp"),
+            CssValue::List(vec![CssValue::Length(4.0, LengthUnit::Dp), CssValue::Length(8.0, LengthUnit::Dp)], Separator::Space)
+        );
+        assert_eq!(CssValue::parse("calc(100% - 2dp)").to_web(), "calc(100% - 2px)");
+
+        let theme: ThemeEntry = serde_json::from_value(json!({
+            "selectors": {
+                ".title": {
+                    "fontSize": "18sp",
+                    "width": "120dp",
+                    "margin": "4dp 8dp",
+                    "boxShadow": "0 2dp 4dp rgba(0, 0, 0, 0.2)",
+                    "border": "1sp solid #000000",
+                    "fontFamily": "\"Inter\", sans-serif"
+                }
+            }
+        }))
+        .unwrap();
+        let mut st = state_with(theme, 2.0);
+        st.register_tailwind_classes(["title".to_string()]);
+        let css = st.css_for_web();
+        for expected in [
+            "font-size:18px;",
+            "width:120px;",
+            "margin:4px 8px;",
+            "box-shadow:0 2px 4px rgba(0, 0, 0, 0.2);",
+            "border-width:1px;",
+            "font-family:\"Inter\", sans-serif;",
+        ] {
+            assert!(css.contains(expected), "missing {} in {}", expected, css);
+        }
+
+        let styles = st.android_styles_for("span", &["title".to_string()]);
+        assert_eq!(styles["fontSize"], json!(36.0));
+        assert_eq!(styles["width"], json!(240));
+        assert_eq!(styles["borderWidth"], json!(2), "sp is a valid border width too");
+
+        // Values are parsed once, when they enter a props map, and keep their source JSON
+        let mut props = CssProps::new();
+        property::merge_property(&mut props, "margin", json!("3dp 7dp").into());
+        assert_eq!(*props["margin"].parsed(), CssValue::parse("3dp 7dp"));
+        assert_eq!(props["margin"], json!("3dp 7dp"));
+        assert_eq!(serde_json::to_value(&props).unwrap(), json!({ "margin": "3dp 7dp" }));
+        property::merge_property(&mut props, "color", json!("#aé").into());
+        assert!(matches!(props["color"].parsed(), CssValue::Keyword(_)));
+    }
+
+    #[test]
+    fn web_output_lowers_native_properties() {
+        let theme: ThemeEntry = serde_json::from_value(json!({
+            "selectors": {
+                ".row": {
+                    "paddingHorizontal": "12dp",
+                    "marginVertical": 4,
+                    "androidGravity": "center",
+                    "textAlignVertical": "top",
+                    "opacity": 0.5
+                },
+                ".both": { "paddingHorizontal": "12px", "padding-inline": "8px" },
+                ".lh-dp": { "lineHeight": 24 },
+                ".lh-ratio": { "lineHeight": "1.5" }
+            }
+        }))
+        .unwrap();
+        let mut st = state_with(theme, 1.0);
+        st.register_tailwind_classes(classes(&["row", "both", "lh-dp", "lh-ratio", "w-full", "h-full", "z-10"]));
+
+        let css = st.css_for_web();
+        assert!(css.contains(".row{margin-block:4px;opacity:0.5;padding-inline:12px;}"), "{}", css);
+        assert!(css.contains(".both{padding-inline:8px;}"), "the CSS spelling wins: {}", css);
+        assert!(css.contains(".w-full{width:100%;}"));
+        assert!(css.contains(".h-full{height:100%;}"));
+        assert!(css.contains(".z-10{z-index:10;}"));
+        assert!(css.contains(".lh-dp{line-height:24px;}"), "{}", css);
+        assert!(css.contains(".lh-ratio{line-height:1.5;}"), "{}", css);
+        for invalid in ["match_parent", "elevation", "android", "padding-horizontal", "text-align-vertical"] {
+            assert!(!css.contains(invalid), "{} in {}", invalid, css);
+        }
+
+        // Native output is unchanged
+        let styles = st.android_styles_for("div", &["row".to_string(), "z-10".to_string()]);
+        assert_eq!(styles["paddingHorizontal"], json!(12));
+        assert_eq!(styles["elevation"], json!(10));
+    }
+
+    #[test]
+    fn composite_android_background() {
+        let theme: ThemeEntry = serde_json::from_value(json!({
+            "selectors": {
+                ".chip": { "backgroundColor": "#ff000080", "rippleColor": "#00000033" }
+            }
+        }))
+        .unwrap();
+        let mut st = state_with(theme, 2.0);
+        let classes = classes(&["chip", "rounded-lg", "rounded-ss-none", "border-2", "border-s-4", "border-red-500", "overflow-hidden"]);
+
+        let styles = st.android_styles_for("div", &classes);
+        assert_eq!(styles["backgroundColor"], json!("#80ff0000"), "flat keys stay");
+        let bg = &styles["androidBackground"];
+        assert_eq!(bg["shape"], json!("rectangle"));
+        assert_eq!(bg["radii"], json!([0, 16, 16, 16]));
+        assert_eq!(bg["fill"], json!("#80ff0000"));
+        assert_eq!(bg["ripple"], json!("#33000000"));
+        assert_eq!(bg["clipChildren"], json!(true));
+        assert_eq!(bg["stroke"]["top"], json!({ "width": 4, "color": "#ef4444", "style": "solid" }));
+        assert_eq!(bg["stroke"]["left"]["width"], json!(8), "start is the left side in LTR");
+        assert_eq!(bg["uniformStroke"], json!(false));
+
+        st.set_layout_direction(direction::LayoutDirection::Rtl);
+        let bg = st.android_styles_for("div", &classes)["androidBackground"].clone();
+        assert_eq!(bg["radii"], json!([16, 0, 16, 16]));
+        assert_eq!(bg["stroke"]["right"]["width"], json!(8));
+        assert_eq!(bg["stroke"]["left"]["width"], json!(4));
+
+        let avatar = st.android_styles_for("div", &["w-10", "h-10", "rounded-full", "bg-blue-500"].map(String::from));
+        assert_eq!(avatar["androidBackground"]["shape"], json!("oval"));
+        let gradient = st.android_styles_for("div", &["bg-gradient-to-r", "from-red-500", "to-blue-500"].map(String::from));
+        assert_eq!(gradient["androidBackground"]["gradient"], gradient["androidGradient"]);
+        assert!(!st.android_styles_for("span", &[]).contains_key("androidBackground"));
+        // Elliptical logical corners land on the physical corner for the layout direction
+        let mut pill = CssProps::new();
+        pill.insert("borderRadius".into(), json!("2px").into());
+        pill.insert("borderStartStartRadius".into(), json!("6px 3px").into());
+        st.themes.get_mut("t").unwrap().selectors.insert(".pill".into(), pill);
+        let bg = st.android_styles_for("div", &["pill".to_string()])["androidBackground"].clone();
+        assert_eq!(bg["radii"], json!([4, 4, 12, 6, 4, 4, 4, 4]), "start-start is top-right in RTL");
+        st.set_layout_direction(direction::LayoutDirection::Ltr);
+        let styles = st.android_styles_for("div", &["pill".to_string()]);
+        assert_eq!(styles["androidCornerRadii"], json!([12, 6, 4, 4, 4, 4, 4, 4]));
+        assert_eq!(styles["borderTopStartRadius"], json!(12));
+    }
+
+    #[test]
+    fn theme_color_functions_and_mix_spaces() {
+        let mut vars = IndexMap::new();
+        vars.insert("primary".to_string(), "#3b82f6".to_string());
+        vars.insert("text".to_string(), "#111827".to_string());
+        vars.insert("bg".to_string(), "#fefce8".to_string());
+        let hex = |v: &str| color::resolve_color(v, None, &vars);
+        assert_eq!(hex("lighten(#808080, 10%)"), "#9a9a9a");
+        assert_eq!(hex("darken(hsl(0 100% 50%), 20%)"), "#990000");
+        assert_eq!(hex("alpha(var(text), 0.6)"), "#11182799");
+        assert_eq!(hex("alpha(var(primary), 50%)"), "#3b82f680");
+        assert_eq!(hex("contrast(var(bg))"), "#000000");
+        assert_eq!(hex("contrast(navy)"), "#ffffff");
+        assert_eq!(hex("contrast(var(primary), var(text), var(bg))"), "#111827");
+        assert_eq!(hex("lighten(alpha(white, 0.5), 0%)"), "#ffffff80");
+        // Relative color syntax
+        assert_eq!(hex("rgb(from var(primary) r g b / 50%)"), "#3b82f680");
+        assert_eq!(hex("rgb(from #102030 calc(r * 2) g b)"), "#202030");
+        assert_eq!(hex("hsl(from red calc(h + 120) s l)"), "#00ff00");
+        assert_eq!(hex("oklch(from #ff0000 l c h)"), "#ff0000");
+        // color-mix interpolation spaces
+        assert_eq!(hex("color-mix(in srgb, red, blue)"), "#800080");
+        assert_eq!(hex("color-mix(in hsl, red, blue)"), "#ff00ff");
+        assert_eq!(hex("color-mix(in hsl longer hue, red, blue)"), "#00ff00");
+        assert_eq!(hex("color-mix(in srgb-linear, black, white)"), "#bcbcbc");
+        assert_eq!(hex("color-mix(in oklab, black, white)"), "#636363");
+        assert_eq!(hex("color-mix(in srgb, red 30%, blue 20%)"), "#99006680");
+        assert_eq!(hex("color-mix(in srgb, transparent, blue)"), "#0000ff80");
+        assert_eq!(hex("color-mix(in bogus, red, blue)"), "color-mix(in bogus, red, blue)");
+
+        // Web output lowers only the non-standard helpers
+        let mut web = CssProps::new();
+        web.insert("color".into(), json!("darken(var(primary), 10%)").into());
+        web.insert("borderColor".into(), json!("color-mix(in oklab, red, blue)").into());
+        assert_eq!(
+            css_props_string(&web, &vars),
+            format!("color:{};border-color:color-mix(in oklab, red, blue);", hex("darken(#3b82f6, 10%)"))
+        );
+    }
+
     #[test]
     fn parse_var_references_basic() {
         // Test basic var() parsing
@@ -2399,24 +4104,28 @@ mod tests {
 
     #[test]
     fn test_android_scrolling_mapping() {
-        let mut state = State::default();
-        state.display_density = 2.0;
-        state.scaled_density = 2.0;
-        state.current_theme = "default".to_string();
+        let mut state = State {
+            display_density: 2.0,
+            scaled_density: 2.0,
+            current_theme: "default".to_string(),
+            ..Default::default()
+        };
         
         let mut themes = IndexMap::new();
-        let mut default_theme = crate::ThemeEntry::default();
-        default_theme.name = Some("Default".to_string());
+        let mut default_theme = crate::ThemeEntry {
+            name: Some("Default".to_string()),
+            ..Default::default()
+        };
         
-        let mut overflow_styles = IndexMap::new();
-        overflow_styles.insert("overflowX".to_string(), serde_json::json!("auto"));
-        overflow_styles.insert("overflowY".to_string(), serde_json::json!("scroll"));
+        let mut overflow_styles = CssProps::new();
+        overflow_styles.insert("overflowX".to_string(), serde_json::json!("auto").into());
+        overflow_styles.insert("overflowY".to_string(), serde_json::json!("scroll").into());
         
         default_theme.selectors.insert(".scroller".to_string(), overflow_styles);
         themes.insert("default".to_string(), default_theme);
         state.themes = themes;
         
-        let styles = state.android_styles_for("div", &vec![".scroller".to_string()]);
+        let styles = state.android_styles_for("div", &[".scroller".to_string()]);
         
         assert_eq!(styles.get("androidScrollHorizontal"), Some(&serde_json::json!(true)));
         assert_eq!(styles.get("androidScrollVertical"), Some(&serde_json::json!(true)));
@@ -2497,8 +4206,8 @@ mod tests {
         variables.insert("color.bg".to_string(), "#ffffff".to_string());
         
         let mut selectors = IndexMap::new();
-        let mut button_props = IndexMap::new();
-        button_props.insert("background-color".to_string(), json!("#2563eb"));
+        let mut button_props = CssProps::new();
+        button_props.insert("background-color".to_string(), json!("#2563eb").into());
         selectors.insert("button".to_string(), button_props);
         
         let default_theme = ThemeEntry {
@@ -2507,6 +4216,11 @@ mod tests {
             selectors,
             variables,
             breakpoints: IndexMap::new(),
+            contrast_pairs: IndexMap::new(),
+            keyframes: IndexMap::new(),
+            fonts: IndexMap::new(),
+            accessibility: Default::default(),
+            units: Default::default(),
         };
         
         themes.insert("default".to_string(), default_theme);
@@ -2535,8 +4249,8 @@ mod tests {
         let mut themes = IndexMap::new();
         let mut selectors = IndexMap::new();
         
-        let mut bg_primary = IndexMap::new();
-        bg_primary.insert("background-color".to_string(), json!("#3b82f6"));
+        let mut bg_primary = CssProps::new();
+        bg_primary.insert("background-color".to_string(), json!("#3b82f6").into());
         selectors.insert(".bg-primary".to_string(), bg_primary);
         
         let default_theme = ThemeEntry {
@@ -2545,6 +4259,11 @@ mod tests {
             selectors,
             variables: IndexMap::new(),
             breakpoints: IndexMap::new(),
+            contrast_pairs: IndexMap::new(),
+            keyframes: IndexMap::new(),
+            fonts: IndexMap::new(),
+            accessibility: Default::default(),
+            units: Default::default(),
         };
         
         themes.insert("default".to_string(), default_theme);
@@ -2564,10 +4283,10 @@ mod tests {
         let mut themes = IndexMap::new();
         let mut selectors = IndexMap::new();
         
-        let mut props = IndexMap::new();
+        let mut props = CssProps::new();
         // Use camelCase property which should be converted to kebab-case for web
-        props.insert("backgroundColor".to_string(), json!("#ffffff"));
-        props.insert("borderTopWidth".to_string(), json!(1));
+        props.insert("backgroundColor".to_string(), json!("#ffffff").into());
+        props.insert("borderTopWidth".to_string(), json!(1).into());
         selectors.insert("body".to_string(), props);
         
         let default_theme = ThemeEntry {
@@ -2576,6 +4295,11 @@ mod tests {
             selectors,
             variables: IndexMap::new(),
             breakpoints: IndexMap::new(),
+            contrast_pairs: IndexMap::new(),
+            keyframes: IndexMap::new(),
+            fonts: IndexMap::new(),
+            accessibility: Default::default(),
+            units: Default::default(),
         };
         
         themes.insert("default".to_string(), default_theme);
@@ -2591,7 +4315,8 @@ mod tests {
         println!("[test_css_kebab_case_conversion] css: {}", css);
         
         assert!(css.contains("background-color:#ffffff;"));
-        assert!(css.contains("border-top-width:1;"));
+        // Bare numbers are dp, which is px on the web
+        assert!(css.contains("border-top-width:1px;"));
         assert!(!css.contains("backgroundColor:"));
         assert!(!css.contains("borderTopWidth:"));
     }
@@ -0,0 +1,53 @@
+use serde_json::json;
+use crate::property::{CssProperty, Platform};
+use crate::CssProps;
+
+/// React Native-style properties and the CSS properties they lower to on the web
+const WEB_RENAMES: &[(&str, &str)] = &[
+    ("padding-horizontal", "padding-inline"),
+    ("padding-vertical", "padding-block"),
+    ("margin-horizontal", "margin-inline"),
+    ("margin-vertical", "margin-block"),
+    ("elevation", "z-index"),
+];
+
+/// Properties whose bare numbers stay unitless in CSS; everywhere else a JSON number is dp,
+/// which is `px` on the web. `line-height` is not listed: a JSON number is dp there too, and a
+/// multiplier is written as a string (`"1.5"`).
+const UNITLESS: &[&str] = &[
+    "opacity", "z-index", "flex", "flex-grow", "flex-shrink", "order", "font-weight",
+    "aspect-ratio", "animation-iteration-count", "zoom", "fill-opacity", "stroke-opacity",
+];
+
+/// Lower one rule's declarations to valid browser CSS: native spellings are renamed, native
+/// values translated, and properties with no web equivalent dropped. A property that was
+/// also set under its CSS name keeps that value.
+pub fn lower_for_web(props: &CssProps) -> CssProps {
+    let mut out = CssProps::new();
+    for (key, value) in props {
+        let prop = CssProperty::parse(key);
+        let id = WEB_RENAMES.iter().find(|(native, _)| *native == prop.id()).map_or(prop.id(), |(_, web)| *web);
+        if !CssProperty::parse(id).supported_on(Platform::Web) {
+            log::debug!("[lower_for_web] dropping native-only property {}", key);
+            continue;
+        }
+        if id != prop.id() && props.keys().any(|k| CssProperty::parse(k).id() == id) {
+            continue;
+        }
+        let value = match value.raw() {
+            serde_json::Value::Number(n) if !UNITLESS.contains(&id) => json!(format!("{}px", n)).into(),
+            _ => value.clone(),
+        };
+        out.insert(id.to_string(), value);
+    }
+    out
+}
+
+/// Native keywords in an already-resolved value
+pub fn lower_value(value: &str) -> &str {
+    match value.trim() {
+        "match_parent" | "fill_parent" => "100%",
+        "wrap_content" => "fit-content",
+        _ => value,
+    }
+}
@@ -0,0 +1,271 @@
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use crate::utils::{camel_case, kebab_case};
+
+/// Platforms a property has an effect on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Support {
+    All,
+    Web,
+    Native,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Web,
+    Native,
+}
+
+/// A known property: its canonical (kebab-case CSS) id, the longhands it sets when used as a
+/// shorthand, and where it is supported.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PropertyDef {
+    pub id: &'static str,
+    pub longhands: &'static [&'static str],
+    pub support: Support,
+}
+
+const fn def(id: &'static str, longhands: &'static [&'static str], support: Support) -> PropertyDef {
+    PropertyDef { id, longhands, support }
+}
+
+use Support::{All, Native, Web};
+
+static REGISTRY: &[PropertyDef] = &[
+    // Box model
+    def("padding", &["padding-top", "padding-right", "padding-bottom", "padding-left"], All),
+    def("padding-inline", &["padding-inline-start", "padding-inline-end"], All),
+    def("padding-block", &["padding-top", "padding-bottom"], All),
+    def("padding-horizontal", &["padding-left", "padding-right"], Native),
+    def("padding-vertical", &["padding-top", "padding-bottom"], Native),
+    def("margin", &["margin-top", "margin-right", "margin-bottom", "margin-left"], All),
+    def("margin-inline", &["margin-inline-start", "margin-inline-end"], All),
+    def("margin-block", &["margin-top", "margin-bottom"], All),
+    def("margin-horizontal", &["margin-left", "margin-right"], Native),
+    def("margin-vertical", &["margin-top", "margin-bottom"], Native),
+    def("inset", &["top", "right", "bottom", "left"], All),
+    def("border", &["border-width", "border-style", "border-color"], All),
+    def("border-width", &["border-top-width", "border-right-width", "border-bottom-width", "border-left-width"], All),
+    def("border-color", &["border-top-color", "border-right-color", "border-bottom-color", "border-left-color"], All),
+    def("border-style", &["border-top-style", "border-right-style", "border-bottom-style", "border-left-style"], All),
+    def("border-top", &["border-top-width", "border-top-style", "border-top-color"], All),
+    def("border-right", &["border-right-width", "border-right-style", "border-right-color"], All),
+    def("border-bottom", &["border-bottom-width", "border-bottom-style", "border-bottom-color"], All),
+    def("border-left", &["border-left-width", "border-left-style", "border-left-color"], All),
+    def(
+        "border-radius",
+        &["border-top-left-radius", "border-top-right-radius", "border-bottom-right-radius", "border-bottom-left-radius"],
+        All,
+    ),
+    def("outline", &["outline-width", "outline-style", "outline-color"], All),
+    def("gap", &["row-gap", "column-gap"], All),
+    def("flex", &["flex-grow", "flex-shrink", "flex-basis"], All),
+    def("flex-flow", &["flex-direction", "flex-wrap"], All),
+    def("place-content", &["align-content", "justify-content"], All),
+    def("place-items", &["align-items", "justify-items"], All),
+    def("place-self", &["align-self", "justify-self"], All),
+    def("overflow", &["overflow-x", "overflow-y"], All),
+    def("background", &["background-color", "background-image"], All),
+    def("font", &["font-style", "font-weight", "font-size", "line-height", "font-family"], All),
+    def(
+        "transition",
+        &["transition-property", "transition-duration", "transition-timing-function", "transition-delay"],
+        All,
+    ),
+    def(
+        "animation",
+        &[
+            "animation-name", "animation-duration", "animation-timing-function", "animation-delay",
+            "animation-iteration-count", "animation-direction", "animation-fill-mode",
+        ],
+        All,
+    ),
+    // Text
+    def("color", &[], All),
+    def("font-family", &[], All),
+    def("font-size", &[], All),
+    def("font-style", &[], All),
+    def("font-weight", &[], All),
+    def("line-height", &[], All),
+    def("letter-spacing", &[], All),
+    def("text-align", &[], All),
+    def("text-transform", &[], All),
+    def("white-space", &[], All),
+    def("visibility", &[], All),
+    def("cursor", &[], All),
+    def("direction", &[], All),
+    def("placeholder-color", &[], All),
+    // Platform-specific
+    def("elevation", &[], Native),
+    def("text-align-vertical", &[], Native),
+    def("include-font-padding", &[], Native),
+    def("tint-color", &[], Native),
+    def("resize-mode", &[], Native),
+    def("ripple-color", &[], Native),
+    def("will-change", &[], Web),
+    def("content", &[], Web),
+    def("backdrop-filter", &[], Web),
+];
+
+static BY_ID: Lazy<IndexMap<&'static str, &'static PropertyDef>> = Lazy::new(|| REGISTRY.iter().map(|d| (d.id, d)).collect());
+
+/// Shorthand for each longhand; the first (most general) shorthand wins, e.g. `padding` over
+/// `padding-block` for `padding-top`.
+static SHORTHAND_OF: Lazy<IndexMap<&'static str, &'static PropertyDef>> = Lazy::new(|| {
+    let mut map = IndexMap::new();
+    for d in REGISTRY {
+        for longhand in d.longhands {
+            map.entry(*longhand).or_insert(d);
+        }
+    }
+    map
+});
+
+/// A property name in canonical form. `backgroundColor` and `background-color` parse to the
+/// same property; custom properties (`--brand`) are kept verbatim.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CssProperty(String);
+
+impl CssProperty {
+    pub fn parse(name: &str) -> CssProperty {
+        let name = name.trim();
+        if name.starts_with("--") || !name.chars().any(|c| c.is_ascii_uppercase()) {
+            return CssProperty(name.to_string());
+        }
+        // Vendor prefixes are capitalized in camelCase (`WebkitLineClamp`, but `msTransform`)
+        let vendor = ["Webkit", "Moz", "ms"].iter().any(|p| name.starts_with(p) && name[p.len()..].starts_with(|c: char| c.is_ascii_uppercase()));
+        let kebab = kebab_case(name);
+        CssProperty(if vendor { format!("-{}", kebab) } else { kebab })
+    }
+
+    /// Canonical kebab-case id
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    /// camelCase spelling used by native output
+    pub fn camel(&self) -> String {
+        if self.is_custom() {
+            return self.0.clone();
+        }
+        camel_case(self.0.strip_prefix("-ms-").map(|rest| format!("ms-{}", rest)).as_deref().unwrap_or(&self.0))
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.0.starts_with("--")
+    }
+
+    pub fn def(&self) -> Option<&'static PropertyDef> {
+        BY_ID.get(self.0.as_str()).copied()
+    }
+
+    /// Longhands set by this property (empty for longhands and unknown properties)
+    pub fn longhands(&self) -> &'static [&'static str] {
+        self.def().map_or(&[], |d| d.longhands)
+    }
+
+    /// Shorthand that resets this property, if any
+    pub fn shorthand(&self) -> Option<&'static PropertyDef> {
+        SHORTHAND_OF.get(self.0.as_str()).copied()
+    }
+
+    /// Unknown properties are assumed to work everywhere; `android-*` hints are native only
+    pub fn supported_on(&self, platform: Platform) -> bool {
+        let support = match self.def() {
+            Some(d) => d.support,
+            None if self.0.starts_with("android-") => Native,
+            None => self.shorthand().map_or(All, |d| d.support),
+        };
+        matches!((support, platform), (All, _) | (Web, Platform::Web) | (Native, Platform::Native))
+    }
+}
+
+/// Every longhand a property sets, recursively (`border` -> `border-top-width`, ...)
+fn all_longhands(id: &str, out: &mut Vec<&'static str>) {
+    if let Some(d) = BY_ID.get(id) {
+        for longhand in d.longhands {
+            out.push(longhand);
+            all_longhands(longhand, out);
+        }
+    }
+}
+
+/// Ids each registered property resets: itself, its longhands, and every other shorthand
+/// those longhands fully cover (`padding` resets `padding-horizontal`).
+static RESETS: Lazy<IndexMap<&'static str, Vec<&'static str>>> = Lazy::new(|| {
+    let covered = |id: &str| {
+        let mut out = Vec::new();
+        all_longhands(id, &mut out);
+        out
+    };
+    REGISTRY
+        .iter()
+        .map(|d| {
+            let mut reset = vec![d.id];
+            all_longhands(d.id, &mut reset);
+            for other in REGISTRY.iter().filter(|o| o.id != d.id && !o.longhands.is_empty()) {
+                if covered(other.id).iter().all(|l| reset.contains(l)) && !reset.contains(&other.id) {
+                    reset.push(other.id);
+                }
+            }
+            (d.id, reset)
+        })
+        .collect()
+});
+
+/// Aggregates native output keeps next to the longhands it expands them into
+/// (`paddingHorizontal` beside `paddingLeft` and `paddingRight`)
+const NATIVE_AGGREGATES: &[&str] = &[
+    "padding", "padding-horizontal", "padding-vertical", "margin", "margin-horizontal", "margin-vertical", "border-radius",
+];
+
+/// Native aggregate keys (camelCase) made stale by writing each property: those covering one
+/// of its longhands, other than the property itself.
+static STALE_AGGREGATES: Lazy<IndexMap<&'static str, Vec<String>>> = Lazy::new(|| {
+    let mut map: IndexMap<&'static str, Vec<String>> = IndexMap::new();
+    for aggregate in NATIVE_AGGREGATES {
+        let mut covered = Vec::new();
+        all_longhands(aggregate, &mut covered);
+        for d in REGISTRY.iter().map(|d| d.id).chain(covered.iter().copied()) {
+            let mut sets = vec![d];
+            all_longhands(d, &mut sets);
+            if d != *aggregate && sets.iter().any(|l| covered.contains(l)) {
+                let stale = map.entry(d).or_default();
+                let key = CssProperty(aggregate.to_string()).camel();
+                if !stale.contains(&key) {
+                    stale.push(key);
+                }
+            }
+        }
+    }
+    map
+});
+
+/// Merge `value` for `name` into props keyed by canonical id. A conflicting spelling collapses
+/// onto the existing key, the latest write moves to the end so it wins in declaration order,
+/// and a shorthand drops the longhands it resets. Every props map is built through here, so
+/// its keys are canonical ids and resets are plain key lookups.
+pub fn merge_property<V>(into: &mut IndexMap<String, V>, name: &str, value: V) {
+    let prop = CssProperty::parse(name);
+    match RESETS.get(prop.id()) {
+        Some(reset) => {
+            for id in reset {
+                into.shift_remove(*id);
+            }
+        }
+        None => {
+            into.shift_remove(prop.id());
+        }
+    }
+    into.insert(prop.0, value);
+}
+
+/// Drop native aggregate keys (`paddingHorizontal`, `padding`, ...) from camelCase native
+/// styles before `id` is written, since they would no longer describe every side.
+pub fn clear_stale_aggregates(styles: &mut IndexMap<String, serde_json::Value>, id: &str) {
+    if let Some(stale) = STALE_AGGREGATES.get(id) {
+        for key in stale {
+            styles.shift_remove(key);
+        }
+    }
+}
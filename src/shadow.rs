@@ -0,0 +1,118 @@
+use indexmap::IndexMap;
+use serde_json::json;
+use crate::utils::split_top_level;
+use crate::units::UnitContext;
+
+/// One layer of a `box-shadow` / `text-shadow` list. Lengths are in dp (CSS px).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shadow {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub blur: f32,
+    pub spread: f32,
+    /// Raw color token; `None` means `currentColor`
+    pub color: Option<String>,
+    pub inset: bool,
+}
+
+/// Parse a shadow list, resolving lengths to dp with `units`. `none`/empty yields no layers;
+/// malformed layers are skipped. Text shadows use the same grammar without `spread` and `inset`.
+pub fn parse_shadow_list(value: &str, units: &UnitContext) -> Vec<Shadow> {
+    let value = value.trim();
+    if value.is_empty() || value == "none" {
+        return Vec::new();
+    }
+    split_top_level(value, ',').iter().filter_map(|layer| parse_shadow(layer, units)).collect()
+}
+
+fn parse_shadow(layer: &str, units: &UnitContext) -> Option<Shadow> {
+    let mut lengths = Vec::new();
+    let mut color = None;
+    let mut inset = false;
+    for token in split_top_level(layer, ' ') {
+        if token == "inset" {
+            inset = true;
+        } else if let Some(len) = units.to_dp(&token) {
+            lengths.push(len);
+        } else if color.is_none() {
+            color = Some(token);
+        } else {
+            return None;
+        }
+    }
+    if lengths.len() < 2 || lengths.len() > 4 {
+        return None;
+    }
+    Some(Shadow {
+        offset_x: lengths[0],
+        offset_y: lengths[1],
+        blur: lengths.get(2).copied().unwrap_or(0.0).max(0.0),
+        spread: lengths.get(3).copied().unwrap_or(0.0),
+        color,
+        inset,
+    })
+}
+
+/// Estimate a Material elevation (dp) from the outer layers of a shadow.
+///
+/// A layer casts roughly as far as its vertical offset plus blur, reduced by negative spread;
+/// the 0.375 factor lines Tailwind's scale up with Material (`shadow-lg` -> 8dp, `shadow-2xl` -> 24dp).
+pub fn estimate_elevation(shadows: &[Shadow]) -> f32 {
+    shadows
+        .iter()
+        .filter(|s| !s.inset)
+        .map(|s| (0.375 * (s.offset_y.abs() + s.blur + s.spread)).max(0.0))
+        .fold(0.0, f32::max)
+}
+
+/// Android output for a `box-shadow`: elevation, ambient/spot colors and the structured layer list.
+/// The spot (key light) color comes from the layer with the largest vertical offset, the ambient
+/// color from the one with the smallest.
+pub fn apply_box_shadow(
+    styles: &mut IndexMap<String, serde_json::Value>,
+    shadows: &[Shadow],
+    units: &UnitContext,
+    current_color: Option<&str>,
+    vars: &IndexMap<String, String>,
+) {
+    let outer: Vec<&Shadow> = shadows.iter().filter(|s| !s.inset).collect();
+    if let (Some(spot), Some(ambient)) = (
+        outer.iter().max_by(|a, b| a.offset_y.abs().total_cmp(&b.offset_y.abs())),
+        outer.iter().min_by(|a, b| a.offset_y.abs().total_cmp(&b.offset_y.abs())),
+    ) {
+        styles.insert("elevation".to_string(), json!(crate::dp_to_px(estimate_elevation(shadows).round(), units.density)));
+        styles.insert("androidShadowSpotColor".to_string(), json!(layer_color(spot, current_color, vars)));
+        styles.insert("androidShadowAmbientColor".to_string(), json!(layer_color(ambient, current_color, vars)));
+    }
+    let layers: Vec<serde_json::Value> = shadows.iter().map(|s| json!({
+        "offsetX": crate::dp_to_px(s.offset_x, units.density),
+        "offsetY": crate::dp_to_px(s.offset_y, units.density),
+        "blur": crate::dp_to_px(s.blur, units.density),
+        "spread": crate::dp_to_px(s.spread, units.density),
+        "color": layer_color(s, current_color, vars),
+        "inset": s.inset,
+    })).collect();
+    styles.insert("androidShadows".to_string(), json!(layers));
+}
+
+/// Android output for a `text-shadow`; each layer maps onto `TextView.setShadowLayer`.
+pub fn apply_text_shadow(
+    styles: &mut IndexMap<String, serde_json::Value>,
+    shadows: &[Shadow],
+    units: &UnitContext,
+    current_color: Option<&str>,
+    vars: &IndexMap<String, String>,
+) {
+    let layers: Vec<serde_json::Value> = shadows.iter().map(|s| json!({
+        "dx": crate::dp_to_px(s.offset_x, units.density),
+        "dy": crate::dp_to_px(s.offset_y, units.density),
+        "radius": crate::dp_to_px(s.blur, units.density),
+        "color": layer_color(s, current_color, vars),
+    })).collect();
+    styles.insert("androidTextShadows".to_string(), json!(layers));
+}
+
+fn layer_color(shadow: &Shadow, current_color: Option<&str>, vars: &IndexMap<String, String>) -> String {
+    let raw = shadow.color.as_deref().unwrap_or("currentColor");
+    crate::color::resolve_color(raw, current_color, vars)
+}
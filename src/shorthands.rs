@@ -1,60 +1,58 @@
-use indexmap::IndexMap;
-use crate::units::parse_and_convert_to_px;
+use crate::utils::split_top_level;
 
-pub fn expand_shorthands(styles: &mut IndexMap<String, serde_json::Value>) {
-    if let Some(ph) = styles.get("paddingHorizontal").cloned() {
-        styles.entry("paddingLeft".into()).or_insert(ph.clone());
-        styles.entry("paddingRight".into()).or_insert(ph.clone());
-    }
-    if let Some(pv) = styles.get("paddingVertical").cloned() {
-        styles.entry("paddingTop".into()).or_insert(pv.clone());
-        styles.entry("paddingBottom".into()).or_insert(pv.clone());
-    }
-    if let Some(p) = styles.get("padding").cloned() {
-        styles.entry("paddingTop".into()).or_insert(p.clone());
-        styles.entry("paddingBottom".into()).or_insert(p.clone());
-        styles.entry("paddingLeft".into()).or_insert(p.clone());
-        styles.entry("paddingRight".into()).or_insert(p.clone());
-    }
-    if let Some(mh) = styles.get("marginHorizontal").cloned() {
-        styles.entry("marginLeft".into()).or_insert(mh.clone());
-        styles.entry("marginRight".into()).or_insert(mh.clone());
-    }
-    if let Some(mv) = styles.get("marginVertical").cloned() {
-        styles.entry("marginTop".into()).or_insert(mv.clone());
-        styles.entry("marginBottom".into()).or_insert(mv.clone());
-    }
-    if let Some(m) = styles.get("margin").cloned() {
-        styles.entry("marginTop".into()).or_insert(m.clone());
-        styles.entry("marginBottom".into()).or_insert(m.clone());
-        styles.entry("marginLeft".into()).or_insert(m.clone());
-        styles.entry("marginRight".into()).or_insert(m.clone());
-    }
-    if let Some(r) = styles.get("borderRadius").cloned() {
-        styles.entry("borderTopLeftRadius".into()).or_insert(r.clone());
-        styles.entry("borderTopRightRadius".into()).or_insert(r.clone());
-        styles.entry("borderBottomLeftRadius".into()).or_insert(r.clone());
-        styles.entry("borderBottomRightRadius".into()).or_insert(r.clone());
-    }
+/// Box shorthands and the kebab-case longhands they expand to, in top/right/bottom/left order
+/// (top-left/top-right/bottom-right/bottom-left for `border-radius`).
+const BOX_SHORTHANDS: &[(&str, [&str; 4])] = &[
+    ("padding", ["padding-top", "padding-right", "padding-bottom", "padding-left"]),
+    ("margin", ["margin-top", "margin-right", "margin-bottom", "margin-left"]),
+    ("inset", ["top", "right", "bottom", "left"]),
+    ("border-width", ["border-top-width", "border-right-width", "border-bottom-width", "border-left-width"]),
+    ("border-color", ["border-top-color", "border-right-color", "border-bottom-color", "border-left-color"]),
+    ("border-style", ["border-top-style", "border-right-style", "border-bottom-style", "border-left-style"]),
+    (
+        "border-radius",
+        ["border-top-left-radius", "border-top-right-radius", "border-bottom-right-radius", "border-bottom-left-radius"],
+    ),
+];
+
+pub fn is_box_shorthand(prop: &str) -> bool {
+    BOX_SHORTHANDS.iter().any(|(name, _)| *name == prop)
+}
+
+/// Whether a shorthand value sets different values per side (more than one token, or `/` radii).
+pub fn is_multi_value(value: &str) -> bool {
+    split_top_level(value, ' ').len() > 1 || split_top_level(value, '/').len() > 1
 }
 
-pub fn convert_dimensions_to_px(styles: &mut IndexMap<String, serde_json::Value>, density: f32) {
-    let dimension_props = [
-        "width", "height", "minWidth", "minHeight", "maxWidth", "maxHeight",
-        "padding", "paddingTop", "paddingBottom", "paddingLeft", "paddingRight",
-        "paddingHorizontal", "paddingVertical",
-        "margin", "marginTop", "marginBottom", "marginLeft", "marginRight",
-        "marginHorizontal", "marginVertical",
-        "borderRadius", "borderTopLeftRadius", "borderTopRightRadius", "borderBottomLeftRadius", "borderBottomRightRadius",
-        "borderWidth", "borderTopWidth", "borderBottomWidth", "borderLeftWidth", "borderRightWidth",
-        "gap", "rowGap", "columnGap", "elevation", "fontSize", "lineHeight", "letterSpacing"
-    ];
-    
-    for prop in &dimension_props {
-        if let Some(value) = styles.get(*prop).cloned() {
-            if let Some(converted) = parse_and_convert_to_px(&value, density) {
-                styles.insert(prop.to_string(), converted);
+/// Expand a kebab-case box shorthand with 1–4 values into its longhands using the CSS rules:
+/// one value sets all sides, two set vertical|horizontal, three set top|horizontal|bottom and
+/// four go clockwise from the top. `border-radius` also accepts `<horizontal> / <vertical>`
+/// for elliptical corners; those longhands hold `"<h> <v>"`.
+pub fn expand_box_shorthand(prop: &str, value: &str) -> Option<Vec<(String, String)>> {
+    let (_, longhands) = BOX_SHORTHANDS.iter().find(|(name, _)| *name == prop)?;
+    let sides = if prop == "border-radius" {
+        let halves = split_top_level(value, '/');
+        match halves.as_slice() {
+            [all] => four_sides(all)?,
+            [h, v] => {
+                let (h, v) = (four_sides(h)?, four_sides(v)?);
+                [0, 1, 2, 3].map(|i| if h[i] == v[i] { h[i].clone() } else { format!("{} {}", h[i], v[i]) })
             }
+            _ => return None,
         }
-    }
+    } else {
+        four_sides(value)?
+    };
+    Some(longhands.iter().zip(sides).map(|(k, v)| (k.to_string(), v)).collect())
+}
+
+fn four_sides(value: &str) -> Option<[String; 4]> {
+    let v = split_top_level(value, ' ');
+    Some(match v.as_slice() {
+        [a] => [a.clone(), a.clone(), a.clone(), a.clone()],
+        [a, b] => [a.clone(), b.clone(), a.clone(), b.clone()],
+        [a, b, c] => [a.clone(), b.clone(), c.clone(), b.clone()],
+        [a, b, c, d] => [a.clone(), b.clone(), c.clone(), d.clone()],
+        _ => return None,
+    })
 }
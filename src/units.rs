@@ -1,39 +1,332 @@
-/// Convert dp to pixels using display density
-pub fn dp_to_px(dp: f32, density: f32) -> i32 {
-    (dp * density).round() as i32
-}
-
-/// Convert sp to pixels using scaled density  
-pub fn sp_to_px(sp: f32, scaled_density: f32) -> f32 {
-    sp * scaled_density
-}
-
-/// Parse a CSS value and convert to Android pixels if needed
-pub fn parse_and_convert_to_px(value: &serde_json::Value, density: f32) -> Option<serde_json::Value> {
-    match value {
-        serde_json::Value::Number(n) => {
-            // Bare number treated as dp
-            let dp = n.as_f64()? as f32;
-            Some(serde_json::json!(dp_to_px(dp, density)))
-        }
-        serde_json::Value::String(s) => {
-            // Parse string with units
-            let trimmed = s.trim();
-            if trimmed.ends_with("px") {
-                // Treat px as density-independent pixels (dp) for cross-platform parity
-                let px = trimmed.trim_end_matches("px").trim().parse::<f32>().ok()?;
-                Some(serde_json::json!(dp_to_px(px, density)))
-            } else if trimmed.ends_with("dp") {
-                let dp = trimmed.trim_end_matches("dp").trim().parse::<f32>().ok()?;
-                Some(serde_json::json!(dp_to_px(dp, density)))
-            } else if let Ok(num) = trimmed.parse::<f32>() {
-                // Bare number as string, treat as dp
-                Some(serde_json::json!(dp_to_px(num, density)))
-            } else {
-                // Keep as-is (e.g., "wrap_content", "match_parent")
-                None
+use serde::{Deserialize, Serialize};
+use crate::value::{CssValue, LengthUnit};
+
+/// What a CSS `px` means on native platforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PxUnit {
+    /// `px` is a density-independent pixel (web parity, the default)
+    #[default]
+    Dp,
+    /// `px` is a physical device pixel
+    Px,
+}
+
+/// Theme-level unit configuration; unset values inherit from the parent theme.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnitSettings {
+    /// Size of `1rem` in CSS px (default 16)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rem_base: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub px: Option<PxUnit>,
+}
+
+pub const DEFAULT_REM_BASE: f32 = 16.0;
+
+/// Everything needed to turn a CSS length into Android physical pixels.
+/// Relative lengths are first resolved to dp, then scaled by `density`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitContext {
+    pub density: f32,
+    pub scaled_density: f32,
+    pub px: PxUnit,
+    /// `1rem` in dp
+    pub rem_base: f32,
+    /// The element's resolved font size in dp, used by `em`
+    pub font_size: f32,
+    /// Host-supplied screen size in dp, used by `vw`/`vh`/`vmin`/`vmax`
+    pub viewport_width: Option<f32>,
+    pub viewport_height: Option<f32>,
+}
+
+impl UnitContext {
+    pub fn new(density: f32, scaled_density: f32, settings: &UnitSettings) -> Self {
+        let rem_base = settings.rem_base.unwrap_or(DEFAULT_REM_BASE);
+        UnitContext {
+            density,
+            scaled_density,
+            px: settings.px.unwrap_or_default(),
+            rem_base,
+            font_size: rem_base,
+            viewport_width: None,
+            viewport_height: None,
+        }
+    }
+
+    /// Resolve a length to dp. Returns `None` for percentages, keywords and unknown units,
+    /// and for viewport units when the host hasn't supplied a screen size.
+    pub fn to_dp(&self, value: &str) -> Option<f32> {
+        let value = value.trim();
+        if is_math_function(value) {
+            return self.eval_math(value).ok();
+        }
+        self.value_dp(&CssValue::parse(value))
+    }
+
+    fn value_dp(&self, value: &CssValue) -> Option<f32> {
+        value.length_with(|n, unit| self.length_dp(n as f32, unit).map(f64::from)).map(|dp| dp as f32)
+    }
+
+    fn length_dp(&self, n: f32, unit: LengthUnit) -> Option<f32> {
+        let vw = || self.viewport_width.map(|w| n * w / 100.0);
+        let vh = || self.viewport_height.map(|h| n * h / 100.0);
+        match unit {
+            LengthUnit::Px => Some(match self.px {
+                PxUnit::Dp => n,
+                PxUnit::Px => n / self.density,
+            }),
+            LengthUnit::Dp => Some(n),
+            // sp is dp scaled by the user's font scale
+            LengthUnit::Sp => Some(n * self.font_scale()),
+            LengthUnit::Pt => Some(n * 4.0 / 3.0),
+            LengthUnit::Rem => Some(n * self.rem_base),
+            LengthUnit::Em => Some(n * self.font_size),
+            LengthUnit::Vw => vw(),
+            LengthUnit::Vh => vh(),
+            LengthUnit::Vmin => Some(vw()?.min(vh()?)),
+            LengthUnit::Vmax => Some(vw()?.max(vh()?)),
+        }
+    }
+
+    /// Convert a length to physical pixels (rounded like `dp_to_px`).
+    /// Bare JSON numbers are dp; unresolvable values return `None` and are left as-is.
+    pub fn to_px(&self, value: &serde_json::Value) -> Option<serde_json::Value> {
+        let dp = match value {
+            serde_json::Value::String(s) => self.to_dp(s)?,
+            other => self.value_dp(&CssValue::from_json(other))?,
+        };
+        Some(serde_json::json!(crate::dp_to_px(dp, self.density)))
+    }
+
+    /// Resolve a `font-size` to dp. `em` and `%` are relative to the inherited size, which
+    /// natively is the rem base.
+    pub fn font_size_dp(&self, value: &serde_json::Value) -> Option<f32> {
+        match value {
+            serde_json::Value::Number(n) => n.as_f64().map(|n| n as f32),
+            serde_json::Value::String(s) => {
+                let inherited = UnitContext { font_size: self.rem_base, ..self.clone() };
+                match CssValue::parse(s) {
+                    CssValue::Percentage(p) => Some(p as f32 * self.rem_base / 100.0),
+                    // sp already includes the font scale; undo it so text_px applies it once
+                    CssValue::Length(n, LengthUnit::Sp) => Some(n as f32),
+                    _ => inherited.to_dp(s),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Physical text size for a font size in dp, honouring the user's font scale.
+    pub fn text_px(&self, dp: f32) -> f32 {
+        dp * self.scaled_density
+    }
+
+    /// `line-height`: unitless strings and percentages multiply the font size (CSS semantics);
+    /// JSON numbers are dp like other native values. Scales with the font.
+    pub fn line_height_px(&self, value: &serde_json::Value) -> Option<f32> {
+        let dp = match value {
+            serde_json::Value::Number(n) => n.as_f64()? as f32,
+            serde_json::Value::String(s) => match CssValue::parse(s) {
+                CssValue::Number(factor) => factor as f32 * self.font_size,
+                CssValue::Percentage(pct) => pct as f32 * self.font_size / 100.0,
+                _ => self.to_dp(s)?,
+            },
+            _ => return None,
+        };
+        Some(self.text_px(dp))
+    }
+
+    fn font_scale(&self) -> f32 {
+        if self.density > 0.0 { self.scaled_density / self.density } else { 1.0 }
+    }
+}
+
+/// Whether a value is a CSS math function this engine evaluates
+pub fn is_math_function(value: &str) -> bool {
+    let value = value.trim_start();
+    ["calc(", "min(", "max(", "clamp("].iter().any(|f| value.starts_with(f))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Quantity {
+    Number(f32),
+    /// A length resolved to dp
+    Length(f32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Value(String),
+    Function(String),
+    Op(char),
+    Open,
+    Close,
+    Comma,
+}
+
+impl UnitContext {
+    /// Evaluate `calc()`, `min()`, `max()` and `clamp()` (nested freely) to a length in dp.
+    /// Variables must already be substituted. Errors name what couldn't be resolved, e.g. a
+    /// percentage (unknown parent size), a viewport unit without a screen size, or mixing a
+    /// length with a plain number.
+    pub fn eval_math(&self, expr: &str) -> Result<f32, String> {
+        let tokens = tokenize(expr)?;
+        let mut pos = 0;
+        let value = self.parse_sum(&tokens, &mut pos)?;
+        if pos != tokens.len() {
+            return Err(format!("unexpected input in `{}`", expr));
+        }
+        match value {
+            Quantity::Length(dp) => Ok(dp),
+            // A unitless result is a bare number, which natively means dp
+            Quantity::Number(n) => Ok(n),
+        }
+    }
+
+    fn parse_sum(&self, tokens: &[Token], pos: &mut usize) -> Result<Quantity, String> {
+        let mut acc = self.parse_product(tokens, pos)?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = tokens.get(*pos) {
+            *pos += 1;
+            let rhs = self.parse_product(tokens, pos)?;
+            let sign = if *op == '+' { 1.0 } else { -1.0 };
+            acc = match (acc, rhs) {
+                (Quantity::Number(a), Quantity::Number(b)) => Quantity::Number(a + sign * b),
+                (Quantity::Length(a), Quantity::Length(b)) => Quantity::Length(a + sign * b),
+                _ => return Err("cannot add a length and a number".to_string()),
+            };
+        }
+        Ok(acc)
+    }
+
+    fn parse_product(&self, tokens: &[Token], pos: &mut usize) -> Result<Quantity, String> {
+        let mut acc = self.parse_operand(tokens, pos)?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = tokens.get(*pos) {
+            *pos += 1;
+            let rhs = self.parse_operand(tokens, pos)?;
+            if *op == '/' && rhs == Quantity::Number(0.0) {
+                return Err("division by zero".to_string());
+            }
+            acc = match (*op, acc, rhs) {
+                ('*', Quantity::Number(a), Quantity::Number(b)) => Quantity::Number(a * b),
+                ('*', Quantity::Length(a), Quantity::Number(b)) | ('*', Quantity::Number(b), Quantity::Length(a)) => Quantity::Length(a * b),
+                ('*', _, _) => return Err("cannot multiply two lengths".to_string()),
+                (_, Quantity::Number(a), Quantity::Number(b)) => Quantity::Number(a / b),
+                (_, Quantity::Length(a), Quantity::Number(b)) => Quantity::Length(a / b),
+                _ => return Err("cannot divide by a length".to_string()),
+            };
+        }
+        Ok(acc)
+    }
+
+    fn parse_operand(&self, tokens: &[Token], pos: &mut usize) -> Result<Quantity, String> {
+        let token = tokens.get(*pos).ok_or("unexpected end of expression")?.clone();
+        *pos += 1;
+        match token {
+            Token::Value(v) => self.quantity(&v),
+            Token::Op('-') => match self.parse_operand(tokens, pos)? {
+                Quantity::Number(n) => Ok(Quantity::Number(-n)),
+                Quantity::Length(l) => Ok(Quantity::Length(-l)),
+            },
+            Token::Open => {
+                let inner = self.parse_sum(tokens, pos)?;
+                expect(tokens, pos, &Token::Close)?;
+                Ok(inner)
+            }
+            Token::Function(name) => {
+                let mut args = vec![self.parse_sum(tokens, pos)?];
+                while tokens.get(*pos) == Some(&Token::Comma) {
+                    *pos += 1;
+                    args.push(self.parse_sum(tokens, pos)?);
+                }
+                expect(tokens, pos, &Token::Close)?;
+                apply_function(&name, &args)
+            }
+            other => Err(format!("unexpected {:?}", other)),
+        }
+    }
+
+    fn quantity(&self, token: &str) -> Result<Quantity, String> {
+        if let Ok(n) = token.parse::<f32>() {
+            return Ok(Quantity::Number(n));
+        }
+        if token.ends_with('%') {
+            return Err(format!("`{}` needs the parent size", token));
+        }
+        let viewport_unit = ["vw", "vh", "vmin", "vmax"].iter().any(|u| token.ends_with(u));
+        if viewport_unit && (self.viewport_width.is_none() || self.viewport_height.is_none()) {
+            return Err(format!("`{}` needs the viewport size", token));
+        }
+        self.to_dp(token).map(Quantity::Length).ok_or_else(|| format!("unsupported value `{}`", token))
+    }
+}
+
+fn expect(tokens: &[Token], pos: &mut usize, token: &Token) -> Result<(), String> {
+    if tokens.get(*pos) == Some(token) {
+        *pos += 1;
+        Ok(())
+    } else {
+        Err(format!("expected {:?}", token))
+    }
+}
+
+fn apply_function(name: &str, args: &[Quantity]) -> Result<Quantity, String> {
+    let is_length = matches!(args[0], Quantity::Length(_));
+    let mut values = Vec::with_capacity(args.len());
+    for arg in args {
+        match (arg, is_length) {
+            (Quantity::Length(v), true) | (Quantity::Number(v), false) => values.push(*v),
+            _ => return Err(format!("`{}()` mixes lengths and numbers", name)),
+        }
+    }
+    let result = match (name, values.as_slice()) {
+        ("calc", [v]) => *v,
+        ("min", [first, rest @ ..]) => rest.iter().fold(*first, |a, b| a.min(*b)),
+        ("max", [first, rest @ ..]) => rest.iter().fold(*first, |a, b| a.max(*b)),
+        // CSS: the minimum wins if it exceeds the maximum
+        ("clamp", [lo, v, hi]) => lo.max(v.min(*hi)),
+        _ => return Err(format!("wrong number of arguments for `{}()`", name)),
+    };
+    Ok(if is_length { Quantity::Length(result) } else { Quantity::Number(result) })
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        // A sign directly before a digit is part of the number when it can't be a binary operator
+        let signed_number = (c == '-' || c == '+')
+            && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit() || *n == '.')
+            && !matches!(tokens.last(), Some(Token::Value(_)) | Some(Token::Close));
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '(' => { tokens.push(Token::Open); i += 1; }
+            ')' => { tokens.push(Token::Close); i += 1; }
+            ',' => { tokens.push(Token::Comma); i += 1; }
+            '+' | '-' | '*' | '/' if !signed_number => { tokens.push(Token::Op(c)); i += 1; }
+            _ => {
+                let start = i;
+                i += 1;
+                // `-` continues identifiers (`--x`) and units, but starts an operator after a digit
+                let ends_word = |i: usize| {
+                    chars[i].is_whitespace() || "()*/,+".contains(chars[i]) || (chars[i] == '-' && chars[i - 1].is_ascii_digit())
+                };
+                while i < chars.len() && !ends_word(i) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if chars.get(i) == Some(&'(') {
+                    i += 1;
+                    tokens.push(Token::Function(word.to_ascii_lowercase()));
+                } else if word.starts_with(|ch: char| ch.is_ascii_digit() || ch == '.' || ch == '-' || ch == '+') {
+                    tokens.push(Token::Value(word));
+                } else {
+                    return Err(format!("unresolved `{}`", word));
+                }
             }
         }
-        _ => None
     }
+    Ok(tokens)
 }
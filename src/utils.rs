@@ -1,5 +1,4 @@
 use indexmap::{IndexMap, IndexSet};
-use serde_json::json;
 use crate::CssProps;
 
 pub fn parse_var_references(input: &str) -> Vec<(usize, usize, String)> {
@@ -18,8 +17,7 @@ pub fn parse_var_references(input: &str) -> Vec<(usize, usize, String)> {
             let name_start = i;
             while i < bytes.len() {
                 let c = bytes[i];
-                if (c >= b'a' && c <= b'z') || (c >= b'A' && c <= b'Z') || 
-                   (c >= b'0' && c <= b'9') || c == b'_' || c == b'.' || c == b'-' {
+                if c.is_ascii_alphanumeric() || c == b'_' || c == b'.' || c == b'-' {
                     i += 1;
                 } else { break; }
             }
@@ -45,8 +43,8 @@ pub fn parse_var_references(input: &str) -> Vec<(usize, usize, String)> {
 pub fn resolve_vars(input: &str, vars: &IndexMap<String, String>) -> String {
     let var_refs = parse_var_references(input);
     if var_refs.is_empty() {
-        if input.starts_with('$') {
-            if let Some(val) = vars.get(&input[1..]) { return val.clone(); }
+        if let Some(name) = input.strip_prefix('$') {
+            if let Some(val) = vars.get(name) { return val.clone(); }
         }
         return input.to_string();
     }
@@ -56,8 +54,8 @@ pub fn resolve_vars(input: &str, vars: &IndexMap<String, String>) -> String {
             out.replace_range(*start..*end, val);
         }
     }
-    if out.starts_with('$') {
-        if let Some(val) = vars.get(&out[1..]) { return val.clone(); }
+    if let Some(name) = out.strip_prefix('$') {
+        if let Some(val) = vars.get(name) { return val.clone(); }
     }
     out
 }
@@ -102,7 +100,17 @@ pub fn parse_prefixed_class(class: &str) -> (Option<String>, bool, String) {
     (bp, hover, base)
 }
 
-pub fn css_escape_class(class: &str) -> String { class.replace(':', "\\:") }
+/// Escape characters that are special in CSS selectors (`md:flex`, `w-1/2`, `aspect-[4/3]`, `from-10%`)
+pub fn css_escape_class(class: &str) -> String {
+    let mut out = String::with_capacity(class.len());
+    for ch in class.chars() {
+        if matches!(ch, ':' | '%' | '/' | '[' | ']' | '#' | '.' | '(' | ')' | ',' | '\'' | '"') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
 
 pub fn class_to_selector(class: &str) -> String {
     let (_bp, hover, base) = parse_prefixed_class(class);
@@ -110,95 +118,10 @@ pub fn class_to_selector(class: &str) -> String {
     else { format!(".{}", css_escape_class(&base)) }
 }
 
-pub fn css_value_to_android(value: &serde_json::Value, vars: &IndexMap<String, String>, current_color: Option<&str>) -> serde_json::Value {
-    match value {
-        serde_json::Value::String(s) => {
-            let s2 = resolve_vars(s, vars);
-            // We don't have access to color module here easily without circular deps if we are not careful,
-            // but utils.rs is mostly used by tailwind.rs and tests.
-            // For now, let's just do the basic resolution.
-            if let Some(n) = s2.strip_suffix("px") {
-                if let Ok(parsed) = n.trim().parse::<f64>() { return json!(parsed); }
-            }
-            json!(s2)
-        }
-        _ => value.clone(),
-    }
-}
-
-pub fn merge_android_props(
-    into: &mut IndexMap<String, serde_json::Value>,
-    css_props: &CssProps,
-    vars: &IndexMap<String, String>,
-) {
-    let current_color = css_props.get("color")
-        .and_then(|v| v.as_str())
-        .map(|s| resolve_vars(s, vars))
-        .or_else(|| into.get("color").and_then(|v| v.as_str()).map(|s| s.to_string()));
-
-    for (k, v) in css_props.iter() {
-        let val = css_value_to_android(v, vars, current_color.as_deref());
-        match k.as_str() {
-            "padding" => {
-                into.insert("paddingTop".to_string(), val.clone());
-                into.insert("paddingBottom".to_string(), val.clone());
-                into.insert("paddingLeft".to_string(), val.clone());
-                into.insert("paddingRight".to_string(), val.clone());
-                into.insert("paddingHorizontal".to_string(), val.clone());
-                into.insert("paddingVertical".to_string(), val.clone());
-                into.insert("padding".to_string(), val);
-            }
-            "padding-horizontal" | "paddingHorizontal" => {
-                into.insert("paddingLeft".to_string(), val.clone());
-                into.insert("paddingRight".to_string(), val.clone());
-                into.insert("paddingHorizontal".to_string(), val);
-            }
-            "padding-vertical" | "paddingVertical" => {
-                into.insert("paddingTop".to_string(), val.clone());
-                into.insert("paddingBottom".to_string(), val.clone());
-                into.insert("paddingVertical".to_string(), val);
-            }
-            "margin" => {
-                into.insert("marginTop".to_string(), val.clone());
-                into.insert("marginBottom".to_string(), val.clone());
-                into.insert("marginLeft".to_string(), val.clone());
-                into.insert("marginRight".to_string(), val.clone());
-                into.insert("marginHorizontal".to_string(), val.clone());
-                into.insert("marginVertical".to_string(), val.clone());
-                into.insert("margin".to_string(), val);
-            }
-            "margin-horizontal" | "marginHorizontal" => {
-                into.insert("marginLeft".to_string(), val.clone());
-                into.insert("marginRight".to_string(), val.clone());
-                into.insert("marginHorizontal".to_string(), val);
-            }
-            "margin-vertical" | "marginVertical" => {
-                into.insert("marginTop".to_string(), val.clone());
-                into.insert("marginBottom".to_string(), val.clone());
-                into.insert("marginVertical".to_string(), val);
-            }
-            "border-radius" | "borderRadius" => {
-                into.insert("borderTopLeftRadius".to_string(), val.clone());
-                into.insert("borderTopRightRadius".to_string(), val.clone());
-                into.insert("borderBottomLeftRadius".to_string(), val.clone());
-                into.insert("borderBottomRightRadius".to_string(), val.clone());
-                into.insert("borderRadius".to_string(), val);
-            }
-            "background-color" => { into.insert("backgroundColor".to_string(), val); }
-            "text-align" => { into.insert("textAlign".to_string(), val); }
-            "flex-direction" | "flexDirection" => {
-                let orientation = if val.as_str() == Some("column") || val.as_str() == Some("column-reverse") { "vertical" } else { "horizontal" };
-                into.insert("androidOrientation".to_string(), serde_json::json!(orientation));
-                into.insert("flexDirection".to_string(), val);
-            }
-            _ => { into.insert(camel_case(k), val); }
-        }
-    }
-}
-
+/// Merge `from` over `into`; keys are canonicalized so kebab and camel spellings collapse
 pub fn merge_props(into: &mut CssProps, from: &CssProps) {
     for (k, v) in from.iter() {
-        into.insert(k.clone(), v.clone());
+        crate::property::merge_property(into, k, v.clone());
     }
 }
 
@@ -277,3 +200,26 @@ pub fn split_tag_class_selector(s: &str) -> Option<(String, String)> {
     }
     Some((tag, class_name))
 }
+
+/// Split a CSS value on `sep`, ignoring separators nested inside parentheses.
+/// Whitespace separators collapse, so `split_top_level("1px  rgb(0 0 0)", ' ')` yields two parts.
+pub fn split_top_level(input: &str, sep: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut cur = String::new();
+    for ch in input.chars() {
+        match ch {
+            '(' => { depth += 1; cur.push(ch); }
+            ')' => { depth -= 1; cur.push(ch); }
+            c if depth == 0 && (c == sep || (sep == ' ' && c.is_whitespace())) => {
+                let t = cur.trim();
+                if !t.is_empty() || sep != ' ' { parts.push(t.to_string()); }
+                cur.clear();
+            }
+            _ => cur.push(ch),
+        }
+    }
+    let t = cur.trim();
+    if !t.is_empty() || (sep != ' ' && !parts.is_empty()) { parts.push(t.to_string()); }
+    parts
+}
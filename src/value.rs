@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use crate::color::Color;
+use crate::utils::split_top_level;
+
+/// Length units understood by the unit engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    Px,
+    Dp,
+    Sp,
+    Pt,
+    Rem,
+    Em,
+    Vw,
+    Vh,
+    Vmin,
+    Vmax,
+}
+
+impl LengthUnit {
+    pub fn parse(unit: &str) -> Option<Self> {
+        Some(match unit {
+            "px" => LengthUnit::Px,
+            "dp" | "dip" => LengthUnit::Dp,
+            "sp" => LengthUnit::Sp,
+            "pt" => LengthUnit::Pt,
+            "rem" => LengthUnit::Rem,
+            "em" => LengthUnit::Em,
+            "vw" => LengthUnit::Vw,
+            "vh" => LengthUnit::Vh,
+            "vmin" => LengthUnit::Vmin,
+            "vmax" => LengthUnit::Vmax,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LengthUnit::Px => "px",
+            LengthUnit::Dp => "dp",
+            LengthUnit::Sp => "sp",
+            LengthUnit::Pt => "pt",
+            LengthUnit::Rem => "rem",
+            LengthUnit::Em => "em",
+            LengthUnit::Vw => "vw",
+            LengthUnit::Vh => "vh",
+            LengthUnit::Vmin => "vmin",
+            LengthUnit::Vmax => "vmax",
+        }
+    }
+
+    /// Native-only units; CSS has no `dp`/`sp`, and `1dp` is `1px` on the web by definition
+    pub fn is_native(self) -> bool {
+        matches!(self, LengthUnit::Dp | LengthUnit::Sp)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    Space,
+    Comma,
+}
+
+/// A parsed property value. Stages that care about units and colors parse into this model and
+/// serialize per platform, instead of each picking the raw string apart on its own.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssValue {
+    Number(f64),
+    Length(f64, LengthUnit),
+    Percentage(f64),
+    /// Other dimensions (`45deg`, `150ms`, `1fr`)
+    Dimension(f64, String),
+    /// A parsed color and its source spelling, which web output keeps
+    Color(Color, String),
+    Keyword(String),
+    /// Quoted string, without the quotes
+    String(String),
+    /// `name(args)`, arguments split on top-level commas
+    Function(String, Vec<CssValue>),
+    List(Vec<CssValue>, Separator),
+}
+
+impl CssValue {
+    pub fn parse(input: &str) -> CssValue {
+        let input = input.trim();
+        let commas = split_top_level(input, ',');
+        if commas.len() > 1 {
+            return CssValue::List(commas.iter().map(|p| CssValue::parse(p)).collect(), Separator::Comma);
+        }
+        let words = split_top_level(input, ' ');
+        if words.len() > 1 {
+            return CssValue::List(words.iter().map(|w| CssValue::parse_token(w)).collect(), Separator::Space);
+        }
+        CssValue::parse_token(input)
+    }
+
+    pub fn from_json(value: &serde_json::Value) -> CssValue {
+        match value {
+            serde_json::Value::Number(n) => CssValue::Number(n.as_f64().unwrap_or(0.0)),
+            serde_json::Value::String(s) => CssValue::parse(s),
+            other => CssValue::Keyword(other.to_string()),
+        }
+    }
+
+    fn parse_token(token: &str) -> CssValue {
+        if let Some(quoted) = token.strip_prefix('"').and_then(|t| t.strip_suffix('"'))
+            .or_else(|| token.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')))
+        {
+            return CssValue::String(quoted.to_string());
+        }
+        if let Ok(n) = token.parse::<f64>() {
+            return CssValue::Number(n);
+        }
+        if let Some(n) = token.strip_suffix('%').and_then(|n| n.parse::<f64>().ok()) {
+            return CssValue::Percentage(n);
+        }
+        if let Some(unit_start) = token.find(|c: char| c.is_ascii_alphabetic()).filter(|&i| i > 0) {
+            let (num, unit) = token.split_at(unit_start);
+            if let Ok(n) = num.parse::<f64>() {
+                return match LengthUnit::parse(unit) {
+                    Some(unit) => CssValue::Length(n, unit),
+                    None => CssValue::Dimension(n, unit.to_string()),
+                };
+            }
+        }
+        if let Some(color) = Color::parse(token) {
+            return CssValue::Color(color, token.to_string());
+        }
+        if let Some((name, args)) = token.split_once('(').and_then(|(name, rest)| Some((name, rest.strip_suffix(')')?))) {
+            let args = split_top_level(args, ',').iter().map(|a| CssValue::parse(a)).collect();
+            return CssValue::Function(name.to_string(), args);
+        }
+        CssValue::Keyword(token.to_string())
+    }
+
+    /// Whether the value uses a unit that only exists natively
+    pub fn has_native_units(&self) -> bool {
+        match self {
+            CssValue::Length(_, unit) => unit.is_native(),
+            CssValue::Function(_, items) | CssValue::List(items, _) => items.iter().any(CssValue::has_native_units),
+            _ => false,
+        }
+    }
+
+    /// CSS text with native lengths converted to `px`
+    pub fn to_web(&self) -> String {
+        match self {
+            CssValue::Length(n, unit) if unit.is_native() => format!("{}px", format_number(*n)),
+            CssValue::Function(name, args) => {
+                format!("{}({})", name, args.iter().map(CssValue::to_web).collect::<Vec<_>>().join(", "))
+            }
+            CssValue::List(items, sep) => items.iter().map(CssValue::to_web).collect::<Vec<_>>().join(sep.as_str()),
+            other => other.to_string(),
+        }
+    }
+
+    /// Length in dp, given how to resolve each unit (`None` for non-lengths)
+    pub fn length_with(&self, resolve: impl Fn(f64, LengthUnit) -> Option<f64>) -> Option<f64> {
+        match self {
+            CssValue::Number(n) => Some(*n),
+            CssValue::Length(n, unit) => resolve(*n, *unit),
+            _ => None,
+        }
+    }
+}
+
+impl Separator {
+    fn as_str(self) -> &'static str {
+        match self {
+            Separator::Space => " ",
+            Separator::Comma => ", ",
+        }
+    }
+}
+
+impl std::fmt::Display for CssValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CssValue::Number(n) => write!(f, "{}", format_number(*n)),
+            CssValue::Length(n, unit) => write!(f, "{}{}", format_number(*n), unit.as_str()),
+            CssValue::Percentage(n) => write!(f, "{}%", format_number(*n)),
+            CssValue::Dimension(n, unit) => write!(f, "{}{}", format_number(*n), unit),
+            CssValue::Color(_, source) => f.write_str(source),
+            CssValue::Keyword(k) => f.write_str(k),
+            CssValue::String(s) => write!(f, "\"{}\"", s),
+            CssValue::Function(name, args) => {
+                write!(f, "{}({})", name, args.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(", "))
+            }
+            CssValue::List(items, sep) => {
+                f.write_str(&items.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(sep.as_str()))
+            }
+        }
+    }
+}
+
+/// Web spelling of a resolved value; only values with native units are rewritten, so
+/// everything else keeps its exact source text.
+pub fn web_value(value: &str) -> String {
+    let parsed = CssValue::parse(value);
+    if parsed.has_native_units() { parsed.to_web() } else { value.to_string() }
+}
+
+/// A property value as written in the theme, together with its parsed model. The model is built
+/// once, when the value enters a props map (theme load or utility generation); the value
+/// (de)serializes as the JSON it came from and derefs to it for code that reads raw values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "serde_json::Value", into = "serde_json::Value")]
+pub struct PropValue {
+    raw: serde_json::Value,
+    parsed: CssValue,
+}
+
+impl PropValue {
+    pub fn raw(&self) -> &serde_json::Value {
+        &self.raw
+    }
+
+    pub fn parsed(&self) -> &CssValue {
+        &self.parsed
+    }
+
+    /// Web spelling of the value as written, from the stored model (see `web_value`)
+    pub fn to_web(&self) -> String {
+        match self.raw.as_str() {
+            Some(_) if self.parsed.has_native_units() => self.parsed.to_web(),
+            Some(text) => text.to_string(),
+            None => self.raw.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Value> for PropValue {
+    fn from(raw: serde_json::Value) -> Self {
+        let parsed = CssValue::from_json(&raw);
+        PropValue { raw, parsed }
+    }
+}
+
+impl From<PropValue> for serde_json::Value {
+    fn from(value: PropValue) -> Self {
+        value.raw
+    }
+}
+
+impl std::ops::Deref for PropValue {
+    type Target = serde_json::Value;
+
+    fn deref(&self) -> &serde_json::Value {
+        &self.raw
+    }
+}
+
+impl PartialEq<serde_json::Value> for PropValue {
+    fn eq(&self, other: &serde_json::Value) -> bool {
+        self.raw == *other
+    }
+}
+
+fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}